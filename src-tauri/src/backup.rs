@@ -0,0 +1,235 @@
+use crate::db;
+use crate::error::{FeedMeeError, Result};
+use crate::models::BackupInfo;
+use log::{info, warn};
+use rusqlite::backup::{Backup, StepResult};
+use rusqlite::{Connection, MAIN_DB, OpenFlags};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+const BACKUP_PREFIX: &str = "feedmee-";
+const IMPORTED_PREFIX: &str = "feedmee-imported-";
+const BACKUP_EXT: &str = "sqlite";
+const BUSY_RETRIES: u32 = 20;
+
+/// Directory holding the rotating backups, next to the live database.
+pub fn backup_dir(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("Backups")
+}
+
+/// Writes a consistent snapshot of `conn` into `dir` using the online backup API.
+///
+/// The whole copy is a single backup step, so it runs inside one read
+/// transaction: in WAL mode that neither blocks nor restarts on concurrent writes.
+pub fn create_backup(conn: &Connection, dir: &Path) -> Result<BackupInfo> {
+    fs::create_dir_all(dir)?;
+
+    let name = format!(
+        "{}{}.{}",
+        BACKUP_PREFIX,
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        BACKUP_EXT
+    );
+    let path = dir.join(&name);
+    let partial = path.with_extension("partial");
+
+    {
+        let mut dest = Connection::open(&partial)?;
+        let backup = Backup::new(conn, &mut dest)?;
+        let mut attempts = 0;
+        loop {
+            match backup.step(-1)? {
+                StepResult::Done => break,
+                StepResult::More => continue,
+                _ if attempts < BUSY_RETRIES => {
+                    attempts += 1;
+                    std::thread::sleep(Duration::from_millis(100));
+                },
+                _ => {
+                    return Err(FeedMeeError::Database(
+                        "Database stayed busy during backup".to_string(),
+                    ));
+                },
+            }
+        }
+    }
+    fs::rename(&partial, &path)?;
+
+    info!("Database backed up to {:?}", path);
+    backup_info(&path)
+}
+
+/// Takes a backup if the newest one is older than `interval_hours`, then rotates.
+pub fn backup_if_due(
+    conn: &Connection,
+    dir: &Path,
+    interval_hours: u64,
+    keep: usize,
+) -> Result<Option<BackupInfo>> {
+    let now = chrono::Utc::now().timestamp();
+    let newest = list_backups(dir)?
+        .into_iter()
+        .find(|b| !b.name.starts_with(IMPORTED_PREFIX));
+    if let Some(newest) = newest
+        && now - newest.created_at < (interval_hours as i64).saturating_mul(3600)
+    {
+        return Ok(None);
+    }
+
+    let info = create_backup(conn, dir)?;
+    rotate_backups(dir, keep)?;
+    Ok(Some(info))
+}
+
+/// Deletes the oldest backups so at most `keep` remain. Imported backups are
+/// left alone; the user brought them in and removes them explicitly.
+pub fn rotate_backups(dir: &Path, keep: usize) -> Result<usize> {
+    let backups = list_backups(dir)?;
+    let mut removed = 0;
+    for old in backups
+        .iter()
+        .filter(|b| !b.name.starts_with(IMPORTED_PREFIX))
+        .skip(keep)
+    {
+        match fs::remove_file(dir.join(&old.name)) {
+            Ok(()) => removed += 1,
+            Err(e) => warn!("Failed to remove old backup {}: {}", old.name, e),
+        }
+    }
+    if removed > 0 {
+        info!("Removed {} old backup(s)", removed);
+    }
+    Ok(removed)
+}
+
+/// Lists backups in `dir`, newest first.
+pub fn list_backups(dir: &Path) -> Result<Vec<BackupInfo>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if is_backup_name(&file_name(&path)) {
+            backups.push(backup_info(&path)?);
+        }
+    }
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.name.cmp(&a.name)));
+    Ok(backups)
+}
+
+/// Resolves a backup name from the frontend to a path inside `dir`,
+/// rejecting anything that isn't a plain backup file name.
+pub fn resolve_backup(dir: &Path, name: &str) -> Result<PathBuf> {
+    if !is_backup_name(name) {
+        return Err(FeedMeeError::InvalidInput(format!(
+            "Invalid backup name: {}",
+            name
+        )));
+    }
+    let path = dir.join(name);
+    if !path.is_file() {
+        return Err(FeedMeeError::NotFound(format!("Backup {} not found", name)));
+    }
+    Ok(path)
+}
+
+/// Checks that `path` is an intact FeedMee database before it is restored or imported.
+pub fn verify_backup(path: &Path) -> Result<()> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let result: String = conn.query_row("PRAGMA integrity_check", [], |r| r.get(0))?;
+    if result != "ok" {
+        return Err(FeedMeeError::Database(format!(
+            "Backup failed integrity check: {}",
+            result
+        )));
+    }
+
+    let has_tables: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN ('folders', 'feeds', 'articles')",
+        [],
+        |r| r.get(0),
+    )?;
+    if has_tables != 3 {
+        return Err(FeedMeeError::InvalidInput(
+            "File is not a FeedMee database".to_string(),
+        ));
+    }
+
+    // Restoring it would leave the app on a database it can't migrate or open
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
+    if version > db::schema_version() {
+        return Err(FeedMeeError::InvalidInput(
+            "Backup was made by a newer version of FeedMee".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Replaces the contents of the live database with the backup at `path`.
+///
+/// The backup is verified first and migrated afterwards, so older backups come
+/// back at the current schema version.
+pub fn restore_backup(conn: &mut Connection, path: &Path) -> Result<()> {
+    verify_backup(path)?;
+
+    info!("Restoring database from {:?}", path);
+    conn.restore(MAIN_DB, path, None::<fn(rusqlite::backup::Progress)>)?;
+    db::init_db(conn)?;
+    info!("Database restore completed");
+    Ok(())
+}
+
+/// Copies an external file into `dir` as a new backup after verifying it.
+pub fn import_backup(src: &Path, dir: &Path) -> Result<BackupInfo> {
+    verify_backup(src)?;
+    fs::create_dir_all(dir)?;
+
+    let name = format!(
+        "{}{}.{}",
+        IMPORTED_PREFIX,
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        BACKUP_EXT
+    );
+    let path = dir.join(name);
+    fs::copy(src, &path)?;
+
+    info!("Imported backup from {:?}", src);
+    backup_info(&path)
+}
+
+fn backup_info(path: &Path) -> Result<BackupInfo> {
+    let meta = fs::metadata(path)?;
+    let created_at = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    Ok(BackupInfo {
+        name: file_name(path),
+        size: meta.len(),
+        created_at,
+    })
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn is_backup_name(name: &str) -> bool {
+    name.starts_with(BACKUP_PREFIX)
+        && name.ends_with(&format!(".{}", BACKUP_EXT))
+        && !name.contains(['/', '\\'])
+        && !name.contains("..")
+}
@@ -0,0 +1,185 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+use std::borrow::Cow;
+use url::Url;
+
+/// How far into a document to look for an in-band declaration, as in the HTML prescan.
+const PRESCAN_BYTES: usize = 1024;
+
+/// Decodes an HTML page for scraper or Readability. Precedence follows the HTML
+/// spec: byte order mark, then the `Content-Type` charset, then `<meta charset>`
+/// (or an XML prolog for XHTML), then a statistical guess.
+pub fn decode_html(bytes: &[u8], content_type: Option<&str>, url: &Url) -> String {
+    let encoding = Encoding::for_bom(bytes)
+        .map(|(e, _)| e)
+        .or_else(|| content_type.and_then(header_charset))
+        .or_else(|| meta_charset(bytes))
+        .or_else(|| xml_charset(bytes))
+        .unwrap_or_else(|| guess(bytes, url));
+    let (text, _, _) = encoding.decode(bytes);
+    text.into_owned()
+}
+
+/// Feed XML declaring its own encoding (BOM or prolog) is left for the parser.
+/// A charset given only in `Content-Type` is applied here: the body is transcoded
+/// to UTF-8 and the prolog rewritten to match.
+pub fn feed_to_utf8<'a>(bytes: &'a [u8], content_type: Option<&str>) -> Cow<'a, [u8]> {
+    if Encoding::for_bom(bytes).is_some() {
+        return Cow::Borrowed(bytes);
+    }
+    let Some(encoding) = content_type.and_then(header_charset) else {
+        return Cow::Borrowed(bytes);
+    };
+    if encoding == UTF_8 || xml_charset(bytes).is_some() {
+        return Cow::Borrowed(bytes);
+    }
+
+    let (text, _, _) = encoding.decode(bytes);
+    let text = match text.strip_prefix("<?xml") {
+        Some(rest) => match rest.find("?>") {
+            Some(end) => format!("<?xml version=\"1.0\" encoding=\"UTF-8\"{}", &rest[end..]),
+            None => text.into_owned(),
+        },
+        None => text.into_owned(),
+    };
+    Cow::Owned(text.into_bytes())
+}
+
+fn header_charset(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        Encoding::for_label(value.trim().trim_matches(['"', '\'']).as_bytes())
+    })
+}
+
+/// `<meta charset="x">` or `<meta http-equiv="Content-Type" content="...; charset=x">`.
+fn meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = prescan(bytes);
+    let mut rest = head.as_str();
+    while let Some(start) = rest.find("<meta") {
+        let tag = &rest[start..];
+        let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+        if let Some(encoding) = declared_value(tag, "charset") {
+            // A page can't really be UTF-16 if its markup is readable as ASCII
+            return Some(encoding.output_encoding());
+        }
+        rest = &rest[start + 5..];
+    }
+    None
+}
+
+fn xml_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = prescan(bytes);
+    let prolog = head.strip_prefix("<?xml")?;
+    let prolog = &prolog[..prolog.find("?>")?];
+    declared_value(prolog, "encoding")
+}
+
+/// The first bytes, lowercased; non-ASCII bytes can't be part of a declaration.
+fn prescan(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(&bytes[..bytes.len().min(PRESCAN_BYTES)])
+        .trim_start()
+        .to_ascii_lowercase()
+}
+
+/// Finds `name=value` (value optionally quoted) anywhere in `text`.
+fn declared_value(text: &str, name: &str) -> Option<&'static Encoding> {
+    let (_, after) = text.split_once(name)?;
+    let value = after.trim_start().strip_prefix('=')?.trim_start();
+    let value = value.trim_start_matches(['"', '\'']);
+    let end = value
+        .find(|c: char| !(c.is_ascii_alphanumeric() || "-_.:".contains(c)))
+        .unwrap_or(value.len());
+    Encoding::for_label(&value.as_bytes()[..end])
+}
+
+/// Undeclared pages: valid UTF-8 stays UTF-8, anything else is guessed from the
+/// byte statistics, using the site's TLD as a hint (e.g. `.jp`, `.ru`).
+fn guess(bytes: &[u8], url: &Url) -> &'static Encoding {
+    if std::str::from_utf8(bytes).is_ok() {
+        return UTF_8;
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let tld = url
+        .host_str()
+        .and_then(|host| host.rsplit('.').next())
+        .map(str::as_bytes);
+    detector.guess(tld, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_html, feed_to_utf8};
+    use url::Url;
+
+    fn url() -> Url {
+        Url::parse("https://example.com/").unwrap()
+    }
+
+    /// "café" in windows-1252.
+    const CAFE_1252: &[u8] = b"<p>caf\xe9</p>";
+
+    #[test]
+    fn bom_wins_over_declarations() {
+        let mut bytes = b"\xef\xbb\xbf<meta charset=\"windows-1252\"><p>caf".to_vec();
+        bytes.extend_from_slice("é</p>".as_bytes());
+        let text = decode_html(&bytes, Some("text/html; charset=iso-8859-1"), &url());
+        assert!(text.contains("café"), "{text}");
+    }
+
+    #[test]
+    fn header_charset_wins_over_meta() {
+        let mut bytes = b"<meta charset=\"utf-8\">".to_vec();
+        bytes.extend_from_slice(CAFE_1252);
+        let text = decode_html(&bytes, Some("text/html; charset=\"windows-1252\""), &url());
+        assert!(text.contains("café"), "{text}");
+    }
+
+    #[test]
+    fn meta_charset_without_header() {
+        let mut bytes = b"<html><head><meta charset='windows-1252'></head>".to_vec();
+        bytes.extend_from_slice(CAFE_1252);
+        assert!(decode_html(&bytes, Some("text/html"), &url()).contains("café"));
+
+        let mut bytes =
+            b"<meta http-equiv=\"Content-Type\" content=\"text/html; charset=windows-1252\">"
+                .to_vec();
+        bytes.extend_from_slice(CAFE_1252);
+        assert!(decode_html(&bytes, None, &url()).contains("café"));
+    }
+
+    #[test]
+    fn meta_utf16_is_read_as_utf8() {
+        let mut bytes = b"<meta charset=\"utf-16\">".to_vec();
+        bytes.extend_from_slice("<p>café</p>".as_bytes());
+        assert!(decode_html(&bytes, None, &url()).contains("café"));
+    }
+
+    #[test]
+    fn undeclared_utf8_stays_utf8() {
+        let bytes = "<p>café</p>".as_bytes();
+        assert!(decode_html(bytes, None, &url()).contains("café"));
+    }
+
+    #[test]
+    fn feed_transcoded_only_for_header_charset() {
+        let mut feed = b"<?xml version=\"1.0\"?><rss><title>".to_vec();
+        feed.extend_from_slice(b"caf\xe9</title></rss>");
+        let out = feed_to_utf8(&feed, Some("application/rss+xml; charset=windows-1252"));
+        let out = std::str::from_utf8(&out).unwrap();
+        assert!(
+            out.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"),
+            "{out}"
+        );
+        assert!(out.contains("café"));
+
+        let mut declared = b"<?xml version=\"1.0\" encoding=\"iso-8859-1\"?><rss>".to_vec();
+        declared.extend_from_slice(b"caf\xe9</rss>");
+        let out = feed_to_utf8(&declared, Some("text/xml; charset=windows-1252"));
+        assert_eq!(&*out, declared.as_slice());
+    }
+}
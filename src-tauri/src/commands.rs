@@ -1,7 +1,22 @@
 use crate::{
-    AppState, db,
-    models::{Article, Folder},
+    AppState,
+    auth::{self, FeedCredentials},
+    backup, charset, db, discovery,
+    error::{FeedMeeError, Result},
+    http::{self, HttpClient},
+    local::{self, LocalSource},
+    maintenance,
+    models::{
+        Article, ArticlePage, ArticleQuery, BackupInfo, DbHealth, FeedAuth, FeedAuthInfo,
+        FeedCandidate, FeedSettings, Folder, ResolvedFeedSettings, ScrapeConfig, TrashItem,
+        WatchConfig,
+    },
+    sanitize, scrape,
     settings::{self, AppSettings},
+    sitemap::{self, SitemapEntry},
+    structured,
+    url_policy::UrlPolicy,
+    watch,
 };
 #[allow(unused_imports)]
 use log::{debug, error, info, warn};
@@ -10,6 +25,7 @@ use scraper::{Html, Selector};
 use serde::Serialize;
 use std::fmt::Write;
 use std::fs;
+use std::path::PathBuf;
 
 #[derive(Serialize)]
 pub struct AppInfo {
@@ -20,8 +36,8 @@ pub struct AppInfo {
 }
 
 #[tauri::command]
-pub fn get_app_info(app: tauri::AppHandle) -> Result<AppInfo, String> {
-    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+pub fn get_app_info(app: tauri::AppHandle) -> Result<AppInfo> {
+    let app_data_dir = app.path().app_data_dir()?;
 
     let version = app.package_info().version.to_string();
 
@@ -38,40 +54,56 @@ pub fn get_app_info(app: tauri::AppHandle) -> Result<AppInfo, String> {
 }
 use std::io::Cursor;
 use tauri::{AppHandle, Manager, State};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 use url::Url;
 
+/// Caps page fetches per refresh when a feed is in full-text mode.
+const FULL_TEXT_MAX_PER_REFRESH: usize = 20;
+
 #[tauri::command]
-pub fn get_app_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
+pub fn get_app_settings(state: State<'_, AppState>) -> Result<AppSettings> {
     let settings = state.settings.lock().unwrap();
     Ok(settings.clone())
 }
 
 #[tauri::command]
 pub fn save_app_settings(
-    new_settings: AppSettings,
+    mut new_settings: AppSettings,
     app_handle: AppHandle,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let mut settings_guard = state.settings.lock().unwrap();
-    *settings_guard = new_settings.clone();
+) -> Result<()> {
+    let problems = new_settings.validate();
+    if !problems.is_empty() {
+        return Err(FeedMeeError::InvalidInput(problems.join("; ")));
+    }
+    new_settings.version = settings::SETTINGS_VERSION;
+
+    // Build first, so settings the client rejects (e.g. a bad CA file) aren't saved
+    let new_client = {
+        let current = state.settings.lock().unwrap();
+        http::config_changed(&current, &new_settings)
+            .then(|| HttpClient::from_settings(&new_settings))
+            .transpose()?
+    };
 
-    if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-        settings::save_settings(&app_data_dir, &new_settings);
-        Ok(())
-    } else {
-        Err("Could not determine app data directory".to_string())
+    let app_data_dir = app_handle.path().app_data_dir()?;
+    settings::save_settings(&app_data_dir, &new_settings)?;
+
+    *state.settings.lock().unwrap() = new_settings;
+    if let Some(client) = new_client {
+        *state.http.lock().unwrap() = client;
+        info!("HTTP client rebuilt with new network settings");
     }
+    Ok(())
 }
 
 #[tauri::command]
-pub fn get_shortcuts(
-    app: tauri::AppHandle,
-) -> Result<std::collections::HashMap<String, String>, String> {
-    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+pub fn get_shortcuts(app: tauri::AppHandle) -> Result<std::collections::HashMap<String, String>> {
+    let app_data_dir = app.path().app_data_dir()?;
     let shortcuts_path = app_data_dir.join("shortcuts.json");
 
     if shortcuts_path.exists() {
-        let content = fs::read_to_string(&shortcuts_path).map_err(|e| e.to_string())?;
+        let content = fs::read_to_string(&shortcuts_path)?;
         let shortcuts: std::collections::HashMap<String, String> =
             serde_json::from_str(&content).unwrap_or_default();
         Ok(shortcuts)
@@ -84,149 +116,198 @@ pub fn get_shortcuts(
 pub fn save_shortcuts(
     shortcuts: std::collections::HashMap<String, String>,
     app: tauri::AppHandle,
-) -> Result<(), String> {
-    let app_data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+) -> Result<()> {
+    let app_data_dir = app.path().app_data_dir()?;
     let shortcuts_path = app_data_dir.join("shortcuts.json");
 
-    let json = serde_json::to_string_pretty(&shortcuts).map_err(|e| e.to_string())?;
-    fs::write(shortcuts_path, json).map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(&shortcuts)?;
+    fs::write(shortcuts_path, json)?;
 
     info!("Shortcuts saved to disk");
     Ok(())
 }
 
 #[tauri::command]
-pub fn get_folders_with_feeds(state: State<'_, AppState>) -> Result<Vec<Folder>, String> {
-    let conn = state.db.lock().unwrap();
-    db::get_folders_with_feeds(&conn).map_err(|e| e.to_string())
+pub async fn get_folders_with_feeds(state: State<'_, AppState>) -> Result<Vec<Folder>> {
+    state.db.read(db::get_folders_with_feeds).await
 }
 
 #[tauri::command]
-pub fn get_feed_unread_count(feed_id: i64, state: State<'_, AppState>) -> Result<i64, String> {
-    let conn = state.db.lock().unwrap();
-    db::get_feed_unread_count(&conn, feed_id).map_err(|e| e.to_string())
+pub async fn get_feed_unread_count(feed_id: i64, state: State<'_, AppState>) -> Result<i64> {
+    state
+        .db
+        .read(move |conn| db::get_feed_unread_count(conn, feed_id))
+        .await
 }
 #[tauri::command]
-pub fn get_articles_for_feed(
-    feed_id: i64,
-    limit: usize,
-    offset: usize,
-    sort_desc: bool,
+pub async fn query_articles(
+    mut query: ArticleQuery,
     state: State<'_, AppState>,
-) -> Result<Vec<Article>, String> {
-    let conn = state.db.lock().unwrap();
-    db::get_articles_for_feed(&conn, feed_id, limit, offset, !sort_desc).map_err(|e| e.to_string())
+) -> Result<ArticlePage> {
+    let settings = state.settings.lock().unwrap().clone();
+    state
+        .db
+        .read(move |conn| {
+            if query.sort_asc.is_none() {
+                let overrides = match (query.feed_id, query.folder_id) {
+                    (Some(feed_id), _) => db::get_layered_feed_settings(conn, feed_id)?,
+                    (None, Some(folder_id)) => db::get_feed_settings(conn, "folder", folder_id)?,
+                    _ => FeedSettings::default(),
+                };
+                query.sort_asc = Some(settings.resolve_feed(overrides).sort_order == "asc");
+            }
+            db::query_articles(conn, &query)
+        })
+        .await
 }
 
 #[tauri::command]
-pub fn get_articles_for_folder(
-    folder_id: i64,
-    limit: usize,
-    offset: usize,
-    sort_desc: bool,
-    state: State<'_, AppState>,
-) -> Result<Vec<Article>, String> {
-    let conn = state.db.lock().unwrap();
-    db::get_articles_for_folder(&conn, folder_id, limit, offset, !sort_desc)
-        .map_err(|e| e.to_string())
+pub async fn create_folder(name: String, state: State<'_, AppState>) -> Result<i64> {
+    state
+        .db
+        .write(move |conn| db::create_folder(conn, &name))
+        .await
 }
 
 #[tauri::command]
-pub fn get_latest_articles(
-    cutoff_timestamp: i64,
-    limit: usize,
-    offset: usize,
-    sort_desc: bool,
-    state: State<'_, AppState>,
-) -> Result<Vec<Article>, String> {
-    let conn = state.db.lock().unwrap();
-    db::get_latest_articles(&conn, cutoff_timestamp, limit, offset, !sort_desc)
-        .map_err(|e| e.to_string())
+pub async fn mark_article_saved(id: i64, is_saved: bool, state: State<'_, AppState>) -> Result<()> {
+    state
+        .db
+        .write(move |conn| db::update_article_saved(conn, id, is_saved))
+        .await
 }
 
 #[tauri::command]
-pub fn get_saved_articles(
-    limit: usize,
-    offset: usize,
-    sort_desc: bool,
-    state: State<'_, AppState>,
-) -> Result<Vec<Article>, String> {
-    let conn = state.db.lock().unwrap();
-    db::get_saved_articles(&conn, limit, offset, !sort_desc).map_err(|e| e.to_string())
+pub async fn mark_article_read(id: i64, read: bool, state: State<'_, AppState>) -> Result<()> {
+    state
+        .db
+        .write(move |conn| db::set_article_read(conn, id, read))
+        .await
 }
 
 #[tauri::command]
-pub fn create_folder(name: String, state: State<'_, AppState>) -> Result<i64, String> {
-    let conn = state.db.lock().unwrap();
-    db::create_folder(&conn, &name).map_err(|e| e.to_string())
+pub async fn mark_all_read(target_type: String, id: i64, state: State<'_, AppState>) -> Result<()> {
+    info!("Mark All Read: type={}, id={}", target_type, id);
+    state
+        .db
+        .write(move |conn| {
+            if target_type == "feed" {
+                db::mark_feed_read(conn, id)
+            } else if target_type == "folder" {
+                db::mark_folder_read(conn, id)
+            } else if target_type == "global" {
+                db::mark_global_read(conn)
+            } else {
+                Err(FeedMeeError::InvalidInput(format!(
+                    "Invalid mark-read target: {}",
+                    target_type
+                )))
+            }
+        })
+        .await
 }
 
 #[tauri::command]
-pub fn mark_article_saved(
-    id: i64,
-    is_saved: bool,
-    state: State<'_, AppState>,
-) -> Result<(), String> {
-    let conn = state.db.lock().unwrap();
-    db::update_article_saved(&conn, id, is_saved).map_err(|e| e.to_string())
+pub async fn import_opml(path: String, state: State<'_, AppState>) -> Result<()> {
+    let xml_content = std::fs::read_to_string(&path)?;
+    let document = opml::OPML::from_str(&xml_content)?;
+    state
+        .db
+        .write(move |conn| {
+            let default_folder_id = db::create_folder(conn, "Uncategorized")?;
+
+            for outline in document.body.outlines {
+                if !outline.outlines.is_empty() {
+                    let folder_name = outline.text;
+                    if let Ok(folder_id) = db::create_folder(conn, &folder_name) {
+                        for child in outline.outlines {
+                            if let Some(url) = child.xml_url {
+                                let _ = db::create_feed(conn, &child.text, &url, folder_id, "rss");
+                            }
+                        }
+                    }
+                } else if let Some(url) = outline.xml_url {
+                    let _ = db::create_feed(conn, &outline.text, &url, default_folder_id, "rss");
+                }
+            }
+            db::migrate_url_credentials(conn)
+        })
+        .await
 }
 
+/// Asks where to save the OPML file and writes it there. `false` if the user cancels.
 #[tauri::command]
-pub fn mark_article_read(id: i64, read: bool, state: State<'_, AppState>) -> Result<(), String> {
-    let conn = state.db.lock().unwrap();
-    db::set_article_read(&conn, id, read).map_err(|e| e.to_string())
+pub async fn export_opml_to_path(app: AppHandle, state: State<'_, AppState>) -> Result<bool> {
+    let folders = state.db.read(db::get_folders_with_feeds).await?;
+    let Some(path) = pick_save_path(&app, "OPML File", &["opml"], "feeds.opml").await? else {
+        return Ok(false);
+    };
+    fs::write(&path, render_opml(folders))?;
+    info!("Exported OPML to {:?}", path);
+    Ok(true)
 }
 
-#[tauri::command]
-pub fn mark_all_read(
-    target_type: String,
-    id: i64,
-    state: State<'_, AppState>,
-) -> Result<(), String> {
-    info!("Mark All Read: type={}, id={}", target_type, id);
-    let conn = state.db.lock().unwrap();
-    if target_type == "feed" {
-        db::mark_feed_read(&conn, id).map_err(|e| e.to_string())
-    } else if target_type == "folder" {
-        db::mark_folder_read(&conn, id).map_err(|e| e.to_string())
-    } else if target_type == "global" {
-        db::mark_global_read(&conn).map_err(|e| e.to_string())
-    } else {
-        Err("Invalid type".to_string())
-    }
+/// Shows a native save dialog. Write commands take their destination from here,
+/// never from the webview, so injected script can't choose where files go.
+async fn pick_save_path(
+    app: &AppHandle,
+    filter_name: &str,
+    extensions: &'static [&'static str],
+    default_name: &str,
+) -> Result<Option<PathBuf>> {
+    let dialog = app
+        .dialog()
+        .file()
+        .add_filter(filter_name, extensions)
+        .set_file_name(default_name);
+    let picked = tauri::async_runtime::spawn_blocking(move || dialog.blocking_save_file()).await?;
+    picked
+        .map(|p| p.into_path().map_err(|e| FeedMeeError::Io(e.to_string())))
+        .transpose()
 }
 
-#[tauri::command]
-pub async fn import_opml(path: String, state: State<'_, AppState>) -> Result<(), String> {
-    let xml_content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    let document = opml::OPML::from_str(&xml_content).map_err(|e| e.to_string())?;
-    let conn = state.db.lock().unwrap();
-    let default_folder_id = db::create_folder(&conn, "Uncategorized").map_err(|e| e.to_string())?;
-
-    for outline in document.body.outlines {
-        if !outline.outlines.is_empty() {
-            let folder_name = outline.text;
-            if let Ok(folder_id) = db::create_folder(&conn, &folder_name) {
-                for child in outline.outlines {
-                    if let Some(url) = child.xml_url {
-                        let _ = db::create_feed(&conn, &child.text, &url, folder_id, "rss");
-                    }
-                }
-            }
-        } else if let Some(url) = outline.xml_url {
-            let _ = db::create_feed(&conn, &outline.text, &url, default_folder_id, "rss");
-        }
-    }
-    Ok(())
+/// Shows a native open dialog, so read commands likewise never take a path
+/// from the webview.
+async fn pick_open_path(
+    app: &AppHandle,
+    filter_name: &str,
+    extensions: &'static [&'static str],
+) -> Result<Option<PathBuf>> {
+    let dialog = app.dialog().file().add_filter(filter_name, extensions);
+    let picked = tauri::async_runtime::spawn_blocking(move || dialog.blocking_pick_file()).await?;
+    picked
+        .map(|p| p.into_path().map_err(|e| FeedMeeError::Io(e.to_string())))
+        .transpose()
 }
 
-#[tauri::command]
-pub async fn export_opml(state: State<'_, AppState>) -> Result<String, String> {
-    let folders = {
-        let conn = state.db.lock().unwrap();
-        db::get_folders_with_feeds(&conn).map_err(|e| e.to_string())?
+/// Asks in a native dialog before a local file is read or a command is run,
+/// showing exactly what. The webview can ask for this but never answer it.
+async fn confirm_local_source(app: &AppHandle, source: &LocalSource) -> Result<bool> {
+    let message = match source {
+        LocalSource::File(path) => format!("Read this file as a feed?\n\n{}", path.display()),
+        LocalSource::Exec(command) => format!(
+            "Run this command to update the feed?\n\n{}\n\nIt runs with your permissions on every refresh.",
+            command
+        ),
+        LocalSource::Filter { command, url } => format!(
+            "Fetch {} and pipe it through this command?\n\n{}\n\nIt runs with your permissions on every refresh.",
+            url, command
+        ),
     };
+    let dialog = app
+        .dialog()
+        .message(message)
+        .title("Local Feed")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Allow".to_string(),
+            "Cancel".to_string(),
+        ));
+    Ok(tauri::async_runtime::spawn_blocking(move || dialog.blocking_show()).await?)
+}
 
+/// Local files and commands are left out: they only mean something on this computer.
+fn render_opml(folders: Vec<Folder>) -> String {
     let mut opml = String::new();
     writeln!(&mut opml, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
     writeln!(&mut opml, "<opml version=\"2.0\">").unwrap();
@@ -234,14 +315,19 @@ pub async fn export_opml(state: State<'_, AppState>) -> Result<String, String> {
     writeln!(&mut opml, "  <body>").unwrap();
 
     for folder in folders {
-        if folder.feeds.is_empty() {
+        let feeds: Vec<_> = folder
+            .feeds
+            .iter()
+            .filter(|f| !is_local_feed(&f.feed_type))
+            .collect();
+        if feeds.is_empty() {
             continue;
         }
         let escaped_name = folder.name.replace("\"", "&quot;");
         writeln!(&mut opml, "    <outline text=\"{}\">", escaped_name).unwrap();
-        for feed in &folder.feeds {
+        for feed in feeds {
             let escaped_feed_name = feed.name.replace("\"", "&quot;");
-            let escaped_url = feed.url.replace("\"", "&quot;");
+            let escaped_url = auth::strip_url_credentials(&feed.url).replace("\"", "&quot;");
             writeln!(
                 &mut opml,
                 "      <outline type=\"rss\" text=\"{}\" xmlUrl=\"{}\" />",
@@ -253,45 +339,90 @@ pub async fn export_opml(state: State<'_, AppState>) -> Result<String, String> {
     }
     writeln!(&mut opml, "  </body>").unwrap();
     writeln!(&mut opml, "</opml>").unwrap();
-    Ok(opml)
-}
-
-#[tauri::command]
-pub async fn write_file(path: String, content: String) -> Result<(), String> {
-    std::fs::write(&path, content).map_err(|e| e.to_string())
+    opml
 }
 
 #[tauri::command]
 pub async fn get_article_content(
     url: String,
+    feed_id: Option<i64>,
     state: State<'_, AppState>,
-) -> Result<String, String> {
-    let client = state.http_client.clone();
-    let html = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .text()
-        .await
-        .map_err(|e| e.to_string())?;
+) -> Result<String> {
+    let (policy, credentials) = match feed_id {
+        Some(id) => {
+            let feed_settings = resolve_feed_settings(&state, id).await?;
+            (
+                UrlPolicy::for_feed(feed_settings.allow_private_network),
+                feed_credentials(&state, id).await?,
+            )
+        },
+        None => (UrlPolicy::PUBLIC, None),
+    };
+    let content =
+        extract_article_content(&state.http(), &url, policy, None, credentials.as_ref()).await?;
+    Ok(sanitize::sanitize_html(&content, &url))
+}
+
+/// Fetches and decodes an article page the way its feed is fetched.
+async fn fetch_page(
+    client: &HttpClient,
+    url: &str,
+    policy: UrlPolicy,
+    user_agent: Option<&str>,
+    credentials: Option<&FeedCredentials>,
+) -> Result<String> {
+    let mut request = auth::authorize(client.get(url, policy)?, credentials, url);
+    if let Some(ua) = user_agent {
+        request = request.header(reqwest::header::USER_AGENT, ua);
+    }
+    let response = request.send().await?.error_for_status()?;
+    client.read_html(response).await
+}
+
+/// Fetches a page and extracts its main content with Readability.
+async fn extract_article_content(
+    client: &HttpClient,
+    url: &str,
+    policy: UrlPolicy,
+    user_agent: Option<&str>,
+    credentials: Option<&FeedCredentials>,
+) -> Result<String> {
+    let html = fetch_page(client, url, policy, user_agent, credentials).await?;
 
     let options = ReadabilityOptions::default();
-    let readability =
-        Readability::new(&html, Some(&url), Some(options)).map_err(|e| format!("{:?}", e))?;
-    let article = readability.parse().ok_or("Failed to parse content")?;
-    article.content.ok_or("No content extracted".to_string())
+    let readability = Readability::new(&html, Some(url), Some(options))
+        .map_err(|e| FeedMeeError::parse(format!("{:?}", e)))?;
+    let article = readability
+        .parse()
+        .ok_or_else(|| FeedMeeError::parse("Failed to parse content"))?;
+    article
+        .content
+        .ok_or_else(|| FeedMeeError::parse("No content extracted"))
 }
 
 #[tauri::command]
-pub async fn refresh_feed(feed_id: i64, state: State<'_, AppState>) -> Result<usize, String> {
-    let (url, feed_type, _stored_hash) = {
-        let conn = state.db.lock().unwrap();
-        let feed = db::get_feed(&conn, feed_id).map_err(|e| e.to_string())?;
-        (feed.url, feed.feed_type, feed.content_hash)
-    };
+pub async fn refresh_feed(feed_id: i64, state: State<'_, AppState>) -> Result<usize> {
+    let feed = state
+        .db
+        .read(move |conn| db::get_feed(conn, feed_id))
+        .await?;
+    let (url, feed_type, stored_hash) = (feed.url, feed.feed_type, feed.content_hash);
+    if feed_type == "orphans" {
+        return Ok(0);
+    }
 
-    let client = state.http_client.clone();
+    let feed_settings = resolve_feed_settings(&state, feed_id).await?;
+    let credentials = feed_credentials(&state, feed_id).await?;
+    let client = state.http();
+    let user_agent = feed_settings.user_agent.clone();
+    let policy = UrlPolicy::for_feed(feed_settings.allow_private_network);
+    let get = |url: &str| -> Result<reqwest::RequestBuilder> {
+        let request = auth::authorize(client.get(url, policy)?, credentials.as_ref(), url);
+        Ok(match &user_agent {
+            Some(ua) => request.header(reqwest::header::USER_AGENT, ua),
+            None => request,
+        })
+    };
 
     // Check if this is a website feed (or legacy feed without feed_type)
     let is_website = feed_type == "website" || feed_type.is_empty();
@@ -301,121 +432,437 @@ pub async fn refresh_feed(feed_id: i64, state: State<'_, AppState>) -> Result<us
     );
 
     if is_website {
-        let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
-        let html = response.text().await.map_err(|e| e.to_string())?;
-        let articles = scrape_articles_from_page(&html, &url);
-        let conn = state.db.lock().unwrap();
-        let count = articles
-            .into_iter()
-            .filter_map(|a| db::insert_article(&conn, &a).ok())
-            .sum();
-        let _ = db::update_feed_error(&conn, feed_id, false);
-        return Ok(count);
+        let response = get(&url)?
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| FeedMeeError::from(e).for_feed(feed_id))?;
+        let html = client
+            .read_html(response)
+            .await
+            .map_err(|e| e.for_feed(feed_id))?;
+        let config = state
+            .db
+            .read(move |conn| db::get_scrape_config(conn, feed_id))
+            .await?;
+        let mut articles =
+            scrape::articles(&html, &url, config.as_ref()).map_err(|e| e.for_feed(feed_id))?;
+        if config.is_some_and(|c| c.fetch_article_pages) {
+            fetch_article_metadata(&state, &mut articles, &feed_settings, credentials.as_ref())
+                .await?;
+        }
+        return store_articles(
+            &state,
+            feed_id,
+            articles,
+            &feed_settings,
+            credentials.as_ref(),
+        )
+        .await;
+    }
+
+    if feed_type == "sitemap" {
+        let config = state
+            .db
+            .read(move |conn| db::get_scrape_config(conn, feed_id))
+            .await?;
+        let entries = match fetch_sitemap_entries(&client, get, &url).await {
+            Ok(entries) => entries,
+            Err(e) => return Err(record_feed_error(&state, feed_id, e).await),
+        };
+        let mut articles = sitemap::articles(entries, config.as_ref());
+        if config.is_some_and(|c| c.fetch_article_pages) {
+            fetch_article_metadata(&state, &mut articles, &feed_settings, credentials.as_ref())
+                .await?;
+        }
+        return store_articles(
+            &state,
+            feed_id,
+            articles,
+            &feed_settings,
+            credentials.as_ref(),
+        )
+        .await;
     }
 
-    // Default: RSS/Atom feed handling
-    let result = client.get(&url).send().await;
+    if feed_type == "watch" {
+        let config = state
+            .db
+            .read(move |conn| db::get_watch_config(conn, feed_id))
+            .await?;
+        let text = match fetch_watched_text(&client, get, &url, &config).await {
+            Ok(text) => text,
+            Err(e) => return Err(record_feed_error(&state, feed_id, e).await),
+        };
+        let hash = compute_content_hash(&text);
+        let previous = if stored_hash.as_deref() == Some(hash.as_str()) {
+            Some(text.clone())
+        } else {
+            state
+                .db
+                .read(move |conn| db::get_watch_snapshot(conn, feed_id))
+                .await?
+        };
 
-    match result {
-        Ok(response) => {
-            let content = response.bytes().await.map_err(|e| e.to_string())?;
-            match feed_rs::parser::parse(Cursor::new(content)) {
-                Ok(feed) => {
-                    info!(
-                        "refresh_feed: parsed feed ok, {} entries",
-                        feed.entries.len()
+        // The snapshot only moves forward when a change is reported, so edits
+        // below the threshold add up across refreshes instead of being lost
+        let (articles, save_snapshot) = match previous {
+            None => {
+                let summary = watch::render_snapshot(&text);
+                (
+                    vec![watch_article(&feed.name, &url, "now watching", summary)],
+                    true,
+                )
+            },
+            Some(previous) => {
+                let changes = watch::diff(&previous, &text);
+                if changes.is_empty() {
+                    (vec![], stored_hash.as_deref() != Some(hash.as_str()))
+                } else if (watch::changed_chars(&changes) as i64) < config.min_changed_chars {
+                    debug!(
+                        "refresh_feed: feed {} changed {} chars, below threshold",
+                        feed_id,
+                        watch::changed_chars(&changes)
                     );
-                    let conn = state.db.lock().unwrap();
-                    let mut count = 0;
-                    for entry in feed.entries {
-                        let article_url = entry
-                            .links
-                            .iter()
-                            .find(|l| l.rel.as_deref() == Some("alternate"))
-                            .or(entry.links.first())
-                            .map(|l| l.href.clone())
-                            .unwrap_or_else(|| {
-                                // Generate a stable synthetic URL so the UNIQUE constraint can
-                                // still deduplicate and the article can be stored.
-                                let key = if !entry.id.is_empty() {
-                                    entry.id.clone()
-                                } else {
-                                    entry
-                                        .title
-                                        .as_ref()
-                                        .map(|t| t.content.clone())
-                                        .unwrap_or_default()
-                                };
-                                format!(
-                                    "{}/#{}",
-                                    url.trim_end_matches('/'),
-                                    compute_content_hash(&key)
-                                )
-                            });
-
-                        let article = Article {
-                            id: 0,
-                            feed_id,
-                            title: entry
-                                .title
-                                .map(|t| t.content)
-                                .unwrap_or_else(|| "No Title".to_string()),
-                            author: entry
-                                .authors
-                                .first()
-                                .map(|p| p.name.clone())
-                                .unwrap_or_default(),
-                            summary: entry
-                                .summary
-                                .map(|s| s.content)
-                                .or(entry.content.map(|c| c.body.unwrap_or_default()))
-                                .unwrap_or_default(),
-                            url: article_url,
-                            timestamp: entry
-                                .published
-                                .or(entry.updated)
-                                .map(|d| d.timestamp())
-                                .unwrap_or(0),
-                            is_read: false,
-                            is_saved: false,
-                        };
-                        match db::insert_article(&conn, &article) {
-                            Ok(inserted) => count += inserted,
-                            Err(e) => error!(
-                                "refresh_feed: insert_article failed for url={}: {}",
-                                article.url, e
-                            ),
-                        }
+                    (vec![], false)
+                } else {
+                    let heading = watch::describe(&changes);
+                    let summary = watch::render_diff(&changes);
+                    (
+                        vec![watch_article(&feed.name, &url, &heading, summary)],
+                        true,
+                    )
+                }
+            },
+        };
+
+        // The diff is the content; there is no article page to extract
+        let feed_settings = ResolvedFeedSettings {
+            full_text: false,
+            ..feed_settings
+        };
+        let count = store_articles(
+            &state,
+            feed_id,
+            articles,
+            &feed_settings,
+            credentials.as_ref(),
+        )
+        .await?;
+        if save_snapshot {
+            state
+                .db
+                .write(move |conn| db::save_watch_snapshot(conn, feed_id, &text, &hash))
+                .await?;
+        }
+        return Ok(count);
+    }
+
+    // Default: RSS/Atom feed handling, fetched or read from a local file or command
+    let content = match LocalSource::parse(&url).filter(|_| is_local_feed(&feed_type)) {
+        Some(_) if !state.local_sources.contains(&url) => Err(FeedMeeError::InvalidInput(
+            "Local feed not allowed on this computer yet; allow it in the feed's settings"
+                .to_string(),
+        )),
+        Some(source) => read_local_feed(&client, &get, &source).await,
+        None => match get(&url)?.send().await.and_then(|r| r.error_for_status()) {
+            Ok(response) => client.read_feed(response).await,
+            Err(e) => Err(e.into()),
+        },
+    };
+    let content = match content {
+        Ok(content) => content,
+        Err(e) => return Err(record_feed_error(&state, feed_id, e).await),
+    };
+
+    match feed_rs::parser::parse(Cursor::new(content)) {
+        Ok(feed) => {
+            info!(
+                "refresh_feed: parsed feed ok, {} entries",
+                feed.entries.len()
+            );
+            let articles = feed
+                .entries
+                .into_iter()
+                .map(|entry| {
+                    let article_url = entry
+                        .links
+                        .iter()
+                        .find(|l| l.rel.as_deref() == Some("alternate"))
+                        .or(entry.links.first())
+                        .map(|l| l.href.clone())
+                        .unwrap_or_else(|| {
+                            // Generate a stable synthetic URL so the UNIQUE constraint can
+                            // still deduplicate and the article can be stored.
+                            let key = if !entry.id.is_empty() {
+                                entry.id.clone()
+                            } else {
+                                entry
+                                    .title
+                                    .as_ref()
+                                    .map(|t| t.content.clone())
+                                    .unwrap_or_default()
+                            };
+                            format!(
+                                "{}/#{}",
+                                url.trim_end_matches('/'),
+                                compute_content_hash(&key)
+                            )
+                        });
+
+                    Article {
+                        id: 0,
+                        feed_id,
+                        title: entry
+                            .title
+                            .map(|t| t.content)
+                            .unwrap_or_else(|| "No Title".to_string()),
+                        author: entry
+                            .authors
+                            .first()
+                            .map(|p| p.name.clone())
+                            .unwrap_or_default(),
+                        summary: entry
+                            .summary
+                            .map(|s| s.content)
+                            .or(entry.content.map(|c| c.body.unwrap_or_default()))
+                            .unwrap_or_default(),
+                        url: article_url,
+                        // 0 = undated; ingest falls back to the first-seen time
+                        timestamp: entry
+                            .published
+                            .or(entry.updated)
+                            .map(|d| d.timestamp())
+                            .unwrap_or(0),
+                        is_read: false,
+                        is_saved: false,
+                        fetched_at: 0,
                     }
-                    let _ = db::update_feed_error(&conn, feed_id, false);
-                    Ok(count)
-                },
-                Err(e) => {
-                    error!("refresh_feed: feed_rs parse error for {}: {}", url, e);
-                    let conn = state.db.lock().unwrap();
-                    let _ = db::update_feed_error(&conn, feed_id, true);
-                    Err(format!("Parse error: {}", e))
-                },
-            }
+                })
+                .collect::<Vec<_>>();
+
+            store_articles(
+                &state,
+                feed_id,
+                articles,
+                &feed_settings,
+                credentials.as_ref(),
+            )
+            .await
         },
         Err(e) => {
-            let conn = state.db.lock().unwrap();
-            let _ = db::update_feed_error(&conn, feed_id, true);
-            Err(format!("Network error: {}", e))
+            error!("refresh_feed: feed_rs parse error for {}: {}", url, e);
+            Err(record_feed_error(&state, feed_id, FeedMeeError::from(e)).await)
         },
     }
 }
 
-#[tauri::command]
-pub async fn refresh_all_feeds(state: State<'_, AppState>) -> Result<usize, String> {
-    let feeds = {
-        let conn = state.db.lock().unwrap();
-        let folders = db::get_folders_with_feeds(&conn).map_err(|e| e.to_string())?;
-        folders
-            .into_iter()
-            .flat_map(|f| f.feeds)
-            .collect::<Vec<_>>()
+/// Flags the feed as erroring with `e`'s message and returns `e` tagged with it.
+async fn record_feed_error(
+    state: &State<'_, AppState>,
+    feed_id: i64,
+    e: FeedMeeError,
+) -> FeedMeeError {
+    let message = e.to_string();
+    let _ = state
+        .db
+        .write(move |conn| db::update_feed_error(conn, feed_id, Some(&message)))
+        .await;
+    e.for_feed(feed_id)
+}
+
+/// Whether a feed is read by `read_local_feed` instead of over HTTP.
+fn is_local_feed(feed_type: &str) -> bool {
+    matches!(feed_type, "file" | "exec")
+}
+
+/// A local feed's content, converted to UTF-8 like a fetched one. A filter's
+/// URL is fetched under the feed's network policy before being piped in.
+async fn read_local_feed(
+    client: &HttpClient,
+    get: impl Fn(&str) -> Result<reqwest::RequestBuilder>,
+    source: &LocalSource,
+) -> Result<Vec<u8>> {
+    let max_bytes = client.max_response_bytes();
+    let bytes = match source {
+        LocalSource::File(path) => local::read_file(path, max_bytes).await?,
+        LocalSource::Exec(command) => local::run(command, None, max_bytes).await?,
+        LocalSource::Filter { command, url } => {
+            let response = get(url)?.send().await?.error_for_status()?;
+            let body = client.read_body(response).await?;
+            local::run(command, Some(body), max_bytes).await?
+        },
     };
+    Ok(charset::feed_to_utf8(&bytes, None).into_owned())
+}
+
+/// One change to a watched page, kept at a URL of its own so every change is
+/// stored as a separate article.
+fn watch_article(feed_name: &str, url: &str, heading: &str, summary: String) -> Article {
+    let now = chrono::Utc::now().timestamp();
+    let page = url.split('#').next().unwrap_or(url);
+    Article {
+        id: 0,
+        feed_id: 0, // unused: ingest_articles takes the feed id separately
+        title: format!("{}: {}", feed_name, heading),
+        author: String::new(),
+        summary,
+        url: format!("{}#changed-{}", page, now),
+        timestamp: now,
+        is_read: false,
+        is_saved: false,
+        fetched_at: 0,
+    }
+}
+
+/// Ingests a batch in one transaction. If it fails, nothing from the batch is kept
+/// and the feed is flagged as erroring instead. Applies the feed's full-text and
+/// retention settings around the insert, and sanitizes every summary first.
+async fn store_articles(
+    state: &State<'_, AppState>,
+    feed_id: i64,
+    mut articles: Vec<Article>,
+    feed_settings: &ResolvedFeedSettings,
+    credentials: Option<&FeedCredentials>,
+) -> Result<usize> {
+    if feed_settings.full_text {
+        fetch_full_text(state, &mut articles, feed_settings, credentials).await?;
+    }
+    for article in &mut articles {
+        article.summary = sanitize::sanitize_html(&article.summary, &article.url);
+    }
+
+    let retention_days = feed_settings.retention_days;
+    state
+        .db
+        .write(move |conn| {
+            let result = db::ingest_articles(conn, feed_id, &articles);
+            if let Err(e) = &result {
+                error!(
+                    "refresh_feed: batch insert failed for feed {}: {}",
+                    feed_id, e
+                );
+                let _ = db::update_feed_error(conn, feed_id, Some(&e.to_string()));
+            } else if retention_days > 0 {
+                let cutoff = chrono::Utc::now().timestamp() - retention_days as i64 * 86400;
+                let urls = articles.into_iter().map(|a| a.url).collect::<Vec<_>>();
+                db::prune_articles(conn, feed_id, cutoff, &urls)?;
+            }
+            result
+        })
+        .await
+}
+
+/// Replaces the summary of entries not yet stored with the extracted page text.
+/// Extraction failures keep the feed's own summary.
+async fn fetch_full_text(
+    state: &State<'_, AppState>,
+    articles: &mut [Article],
+    feed_settings: &ResolvedFeedSettings,
+    credentials: Option<&FeedCredentials>,
+) -> Result<()> {
+    let urls = articles.iter().map(|a| a.url.clone()).collect::<Vec<_>>();
+    let existing = state
+        .db
+        .read(move |conn| db::existing_article_urls(conn, &urls))
+        .await?;
+
+    let client = state.http();
+    for article in articles
+        .iter_mut()
+        .filter(|a| !existing.contains(&a.url))
+        .take(FULL_TEXT_MAX_PER_REFRESH)
+    {
+        let content = extract_article_content(
+            &client,
+            &article.url,
+            UrlPolicy::for_feed(feed_settings.allow_private_network),
+            feed_settings.user_agent.as_deref(),
+            credentials,
+        )
+        .await;
+        match content {
+            Ok(content) => article.summary = content,
+            Err(e) => debug!("full text extraction failed for {}: {}", article.url, e),
+        }
+    }
+    Ok(())
+}
+
+/// Fills dates, authors and descriptions of website entries not yet stored from
+/// the structured data on their own pages. Pages that fail to load are skipped.
+async fn fetch_article_metadata(
+    state: &State<'_, AppState>,
+    articles: &mut [Article],
+    feed_settings: &ResolvedFeedSettings,
+    credentials: Option<&FeedCredentials>,
+) -> Result<()> {
+    let urls = articles.iter().map(|a| a.url.clone()).collect::<Vec<_>>();
+    let existing = state
+        .db
+        .read(move |conn| db::existing_article_urls(conn, &urls))
+        .await?;
+
+    let client = state.http();
+    for article in articles
+        .iter_mut()
+        .filter(|a| !existing.contains(&a.url))
+        .take(FULL_TEXT_MAX_PER_REFRESH)
+    {
+        let page = fetch_page(
+            &client,
+            &article.url,
+            UrlPolicy::for_feed(feed_settings.allow_private_network),
+            feed_settings.user_agent.as_deref(),
+            credentials,
+        )
+        .await;
+        match (page, Url::parse(&article.url)) {
+            (Ok(html), Ok(base)) => structured::page_metadata(&html, &base).apply(article),
+            (Err(e), _) => debug!("article metadata fetch failed for {}: {}", article.url, e),
+            (_, Err(e)) => debug!("invalid article url {}: {}", article.url, e),
+        }
+    }
+    Ok(())
+}
+
+async fn resolve_feed_settings(
+    state: &State<'_, AppState>,
+    feed_id: i64,
+) -> Result<ResolvedFeedSettings> {
+    let overrides = state
+        .db
+        .read(move |conn| db::get_layered_feed_settings(conn, feed_id))
+        .await?;
+    Ok(state.settings.lock().unwrap().resolve_feed(overrides))
+}
+
+/// The feed's stored credentials, bound to its URL's origin.
+async fn feed_credentials(
+    state: &State<'_, AppState>,
+    feed_id: i64,
+) -> Result<Option<FeedCredentials>> {
+    let (feed, auth) = state
+        .db
+        .read(move |conn| {
+            Ok((
+                db::get_feed(conn, feed_id)?,
+                db::get_feed_auth(conn, feed_id)?,
+            ))
+        })
+        .await?;
+    Ok(FeedCredentials::new(&feed.url, auth))
+}
+
+#[tauri::command]
+pub async fn refresh_all_feeds(state: State<'_, AppState>) -> Result<usize> {
+    let feeds = state
+        .db
+        .read(db::get_folders_with_feeds)
+        .await?
+        .into_iter()
+        .flat_map(|f| f.feeds)
+        .collect::<Vec<_>>();
 
     let mut total = 0;
     for feed in feeds {
@@ -426,180 +873,518 @@ pub async fn refresh_all_feeds(state: State<'_, AppState>) -> Result<usize, Stri
     Ok(total)
 }
 
-fn compute_content_hash(content: &str) -> String {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-    let mut hasher = DefaultHasher::new();
-    content.hash(&mut hasher);
-    format!("{:x}", hasher.finish())
+/// Refreshes only the feeds whose resolved refresh interval has elapsed.
+#[tauri::command]
+pub async fn refresh_due_feeds(state: State<'_, AppState>) -> Result<usize> {
+    let mut total = 0;
+    for feed_id in get_due_feeds(state.clone()).await? {
+        if let Ok(count) = refresh_feed(feed_id, state.clone()).await {
+            total += count;
+        }
+    }
+    Ok(total)
 }
 
-/// Scrape a listing page for article links. Returns Article structs with empty
-/// summary (content is fetched on-demand via get_article_content). Links are
-/// filtered to same-domain, non-trivial hrefs, and deduplicated.
-fn scrape_articles_from_page(html: &str, page_url: &str) -> Vec<Article> {
-    debug!("scrape_articles_from_page: url={}", page_url);
-    let base = match Url::parse(page_url) {
-        Ok(u) => u,
-        Err(_) => return vec![],
-    };
-    let base_host = base.host_str().unwrap_or("").to_string();
+#[tauri::command]
+pub async fn get_due_feeds(state: State<'_, AppState>) -> Result<Vec<i64>> {
+    let default_interval = state.settings.lock().unwrap().auto_update_interval_minutes;
+    let now = chrono::Utc::now().timestamp();
+    state
+        .db
+        .read(move |conn| db::get_due_feed_ids(conn, default_interval, now))
+        .await
+}
 
-    let document = Html::parse_document(html);
-    let anchor_sel = match Selector::parse("a[href]") {
-        Ok(s) => s,
-        Err(_) => return vec![],
+#[tauri::command]
+pub async fn get_feed_settings(
+    target_type: String,
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<FeedSettings> {
+    state
+        .db
+        .read(move |conn| db::get_feed_settings(conn, &target_type, id))
+        .await
+}
+
+#[tauri::command]
+pub async fn save_feed_settings(
+    target_type: String,
+    id: i64,
+    settings: FeedSettings,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    if let Some(order) = settings.sort_order.as_deref()
+        && !settings::SORT_ORDERS.contains(&order)
+    {
+        return Err(FeedMeeError::InvalidInput(
+            "Sort order must be asc or desc".to_string(),
+        ));
+    }
+    state
+        .db
+        .write(move |conn| db::save_feed_settings(conn, &target_type, id, &settings))
+        .await
+}
+
+/// Settings that actually apply to a feed once feed, folder and global values are resolved.
+#[tauri::command]
+pub async fn get_effective_feed_settings(
+    feed_id: i64,
+    state: State<'_, AppState>,
+) -> Result<ResolvedFeedSettings> {
+    resolve_feed_settings(&state, feed_id).await
+}
+
+/// Which credentials a feed has, without their values.
+#[tauri::command]
+pub async fn get_feed_auth(feed_id: i64, state: State<'_, AppState>) -> Result<FeedAuthInfo> {
+    state
+        .db
+        .read(move |conn| Ok(db::get_feed_auth(conn, feed_id)?.info()))
+        .await
+}
+
+/// Unset fields keep their stored value and empty strings clear them.
+#[tauri::command]
+pub async fn save_feed_auth(
+    feed_id: i64,
+    auth: FeedAuth,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    auth::validate(&auth)?;
+    state
+        .db
+        .write(move |conn| {
+            let merged = auth.merged_onto(db::get_feed_auth(conn, feed_id)?);
+            db::save_feed_auth(conn, feed_id, &merged)
+        })
+        .await?;
+    info!("Updated credentials for feed {}", feed_id);
+    Ok(())
+}
+
+/// A website feed's CSS-selector rules; `None` means links are guessed.
+#[tauri::command]
+pub async fn get_scrape_config(
+    feed_id: i64,
+    state: State<'_, AppState>,
+) -> Result<Option<ScrapeConfig>> {
+    state
+        .db
+        .read(move |conn| db::get_scrape_config(conn, feed_id))
+        .await
+}
+
+#[tauri::command]
+pub async fn save_scrape_config(
+    feed_id: i64,
+    config: Option<ScrapeConfig>,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    if let Some(config) = &config {
+        scrape::validate(config)?;
+    }
+    state
+        .db
+        .write(move |conn| db::save_scrape_config(conn, feed_id, config.as_ref()))
+        .await?;
+    info!("Updated scrape config for feed {}", feed_id);
+    Ok(())
+}
+
+/// Fetches a website feed's page and scrapes it with `config` (saved or not),
+/// without storing anything, so rules can be tried before they are saved.
+#[tauri::command]
+pub async fn preview_scrape(
+    feed_id: i64,
+    config: Option<ScrapeConfig>,
+    state: State<'_, AppState>,
+) -> Result<Vec<Article>> {
+    let feed = state
+        .db
+        .read(move |conn| db::get_feed(conn, feed_id))
+        .await?;
+    let feed_settings = resolve_feed_settings(&state, feed_id).await?;
+    let credentials = feed_credentials(&state, feed_id).await?;
+    let client = state.http();
+    let policy = UrlPolicy::for_feed(feed_settings.allow_private_network);
+    let get = |url: &str| -> Result<reqwest::RequestBuilder> {
+        let request = auth::authorize(client.get(url, policy)?, credentials.as_ref(), url);
+        Ok(match &feed_settings.user_agent {
+            Some(ua) => request.header(reqwest::header::USER_AGENT, ua),
+            None => request,
+        })
     };
 
-    let now = chrono::Utc::now().timestamp();
-    let mut seen = std::collections::HashSet::new();
-    let mut articles = Vec::new();
+    if feed.feed_type == "sitemap" {
+        let entries = fetch_sitemap_entries(&client, get, &feed.url).await?;
+        return Ok(sitemap::articles(entries, config.as_ref()));
+    }
+    let response = get(&feed.url)?.send().await?.error_for_status()?;
+    let html = client.read_html(response).await?;
+    scrape::articles(&html, &feed.url, config.as_ref())
+}
 
-    for el in document.select(&anchor_sel) {
-        let href = match el.value().attr("href") {
-            Some(h) => h,
-            None => continue,
-        };
+/// What a watch feed compares between refreshes.
+#[tauri::command]
+pub async fn get_watch_config(feed_id: i64, state: State<'_, AppState>) -> Result<WatchConfig> {
+    state
+        .db
+        .read(move |conn| db::get_watch_config(conn, feed_id))
+        .await
+}
 
-        let abs = match base.join(href) {
-            Ok(u) => u,
-            Err(_) => continue,
-        };
+#[tauri::command]
+pub async fn save_watch_config(
+    feed_id: i64,
+    config: WatchConfig,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    watch::validate(&config)?;
+    state
+        .db
+        .write(move |conn| db::save_watch_config(conn, feed_id, &config))
+        .await?;
+    info!("Updated watch config for feed {}", feed_id);
+    Ok(())
+}
 
-        // Same domain only; skip fragment-only or javascript links
-        if abs.host_str().unwrap_or("") != base_host {
-            continue;
-        }
-        if abs.path() == base.path() {
-            continue;
+/// Pages listed by a sitemap. An index is followed one level down, reading only
+/// its most recently modified children.
+async fn fetch_sitemap_entries(
+    client: &HttpClient,
+    get: impl Fn(&str) -> Result<reqwest::RequestBuilder>,
+    url: &str,
+) -> Result<Vec<SitemapEntry>> {
+    let found = sitemap::fetch(client, get(url)?).await?;
+    if !found.is_index() {
+        return Ok(found.urls);
+    }
+    let mut entries = Vec::new();
+    for child in found.newest_sitemaps(sitemap::MAX_CHILD_SITEMAPS) {
+        match sitemap::fetch(client, get(&child)?).await {
+            Ok(child) => entries.extend(child.urls),
+            Err(e) => warn!("Skipping sitemap {}: {}", child, e),
         }
+    }
+    Ok(entries)
+}
 
-        let url_str = abs.to_string();
-        if !seen.insert(url_str.clone()) {
-            continue;
-        }
+/// The watched part of the page at `url`; see `watch::snapshot`.
+async fn fetch_watched_text(
+    client: &HttpClient,
+    get: impl Fn(&str) -> Result<reqwest::RequestBuilder>,
+    url: &str,
+    config: &WatchConfig,
+) -> Result<String> {
+    let response = get(url)?.send().await?.error_for_status()?;
+    let html = client.read_html(response).await?;
+    watch::snapshot(&html, config)
+}
 
-        // Extract link text as title; fall back to title attr, then URL slug
-        let anchor_text: String = el.text().collect::<Vec<_>>().join(" ");
-        let anchor_text = anchor_text.split_whitespace().collect::<Vec<_>>().join(" ");
+fn compute_content_hash(content: &str) -> String {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    format!("{:x}", hasher.finish())
+}
 
-        let title = if anchor_text.len() >= 10 {
-            anchor_text.clone()
-        } else if let Some(t) = el.value().attr("title").filter(|t| t.len() >= 10) {
-            t.to_string()
-        } else {
-            // Derive title from the URL path slug (last meaningful segment)
-            let slug = abs
-                .path_segments()
-                .and_then(|mut segs| segs.rfind(|s| !s.is_empty() && s.len() > 3))
-                .unwrap_or("");
-            let from_slug = slug.replace(['-', '_'], " ");
-            if from_slug.len() >= 10 {
-                // Title-case the slug
-                from_slug
-                    .split_whitespace()
-                    .map(|w| {
-                        let mut c = w.chars();
-                        match c.next() {
-                            None => String::new(),
-                            Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .join(" ")
-            } else {
-                debug!("scrape: skipping, no usable title for {}", url_str);
-                continue;
-            }
-        };
+/// Per-feed options chosen when subscribing, saved along with the new feed.
+struct NewFeedOptions {
+    auth: FeedAuth,
+    allow_private_network: bool,
+}
 
-        // Skip navigation/category URLs - only keep URLs that look like articles
-        // (have a path depth of at least 2 segments, or passed the anchor text check)
-        let path_depth = abs
-            .path_segments()
-            .map(|s| s.filter(|p| !p.is_empty()).count())
-            .unwrap_or(0);
-        if path_depth < 2 && anchor_text.len() < 10 {
-            debug!("scrape: skipping shallow nav url {}", url_str);
-            continue;
+impl NewFeedOptions {
+    /// Drops the credentials when the feed ends up on another origin than the
+    /// URL they were entered for, so they are never saved for a different site.
+    fn scoped_to(mut self, entered_url: &str, feed_url: &str) -> Self {
+        if !self.auth.is_empty() && !auth::same_origin(entered_url, feed_url) {
+            info!(
+                "Not saving credentials for {}: entered for {}",
+                feed_url, entered_url
+            );
+            self.auth = FeedAuth::default();
         }
+        self
+    }
 
-        debug!("scrape: accepting {:?} -> {}", title, url_str);
-        articles.push(Article {
-            id: 0,
-            feed_id: 0, // caller sets this
-            title,
-            author: String::new(),
-            summary: String::new(),
-            url: url_str,
-            timestamp: now,
-            is_read: false,
-            is_saved: false,
-        });
+    /// Re-adding an existing feed without credentials keeps the ones it has.
+    fn save(&self, conn: &rusqlite::Connection, feed_id: i64) -> Result<()> {
+        if !self.auth.is_empty() {
+            db::save_feed_auth(conn, feed_id, &self.auth)?;
+        }
+        if self.allow_private_network {
+            let mut settings = db::get_feed_settings(conn, "feed", feed_id)?;
+            settings.allow_private_network = Some(true);
+            db::save_feed_settings(conn, "feed", feed_id, &settings)?;
+        }
+        Ok(())
     }
+}
 
-    articles
+/// The page's `<title>` for naming a new feed, or its URL if it has none.
+fn page_title(html: &str, url: &str) -> String {
+    let document = Html::parse_document(html);
+    Selector::parse("title")
+        .ok()
+        .and_then(|sel| document.select(&sel).next())
+        .map(|el| el.text().collect::<String>().trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| url.to_string())
 }
 
 async fn add_website_feed(
     url: &str,
-    content_bytes: &[u8],
+    html: &str,
     folder_id: Option<i64>,
+    options: NewFeedOptions,
     state: &State<'_, AppState>,
-) -> Result<i64, String> {
-    // Extract page title from <title> tag for the feed name
-    let html = String::from_utf8_lossy(content_bytes);
-    let document = Html::parse_document(&html);
-    let title_sel = Selector::parse("title").ok();
-    let title = title_sel
-        .and_then(|sel| document.select(&sel).next())
-        .map(|el| el.text().collect::<String>())
-        .map(|t| t.trim().to_string())
-        .filter(|t| !t.is_empty())
+) -> Result<i64> {
+    // The parsed document is !Send, so keep it scoped away from the awaits below
+    let title = page_title(html, url);
+    let articles = scrape::articles(html, url, None)?;
+
+    let feed_url = url.to_string();
+    let target = folder_id.unwrap_or(1);
+    let feed_id = state
+        .db
+        .write(move |conn| {
+            db::create_feed(conn, &title, &feed_url, target, "website")?;
+            let feed_id = db::get_feed_id_by_url(conn, &feed_url)?;
+            options.save(conn, feed_id)?;
+            Ok(feed_id)
+        })
+        .await?;
+
+    if articles.is_empty() {
+        return Err(FeedMeeError::parse(format!(
+            "No articles found on page: {}",
+            url
+        )));
+    }
+
+    let feed_settings = resolve_feed_settings(state, feed_id).await?;
+    let credentials = feed_credentials(state, feed_id).await?;
+    store_articles(
+        state,
+        feed_id,
+        articles,
+        &feed_settings,
+        credentials.as_ref(),
+    )
+    .await?;
+
+    Ok(feed_id)
+}
+
+/// Subscribes to a sitemap; pages it lists become the feed's articles.
+async fn add_sitemap_feed(
+    url: &str,
+    folder_id: Option<i64>,
+    options: NewFeedOptions,
+    state: &State<'_, AppState>,
+) -> Result<i64> {
+    let host = Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
         .unwrap_or_else(|| url.to_string());
+    let title = format!("{} (sitemap)", host);
+    let feed_url = url.to_string();
+    let target = folder_id.unwrap_or(1);
+    state
+        .db
+        .write(move |conn| {
+            db::create_feed(conn, &title, &feed_url, target, "sitemap")?;
+            let feed_id = db::get_feed_id_by_url(conn, &feed_url)?;
+            options.save(conn, feed_id)?;
+            Ok(feed_id)
+        })
+        .await
+}
 
-    let feed_id = {
-        let conn = state.db.lock().unwrap();
-        let target = folder_id.unwrap_or(1);
-        db::create_feed(&conn, &title, url, target, "website").map_err(|e| e.to_string())?;
-        conn.query_row("SELECT id FROM feeds WHERE url = ?1", [url], |row| {
-            row.get(0)
+/// Subscribes to changes on a page, named after its `<title>`. The first
+/// refresh records the baseline the later ones are compared against.
+async fn add_watch_feed(
+    url: &str,
+    html: &str,
+    config: WatchConfig,
+    folder_id: Option<i64>,
+    options: NewFeedOptions,
+    state: &State<'_, AppState>,
+) -> Result<i64> {
+    watch::snapshot(html, &config)?;
+    let title = page_title(html, url);
+
+    let feed_url = url.to_string();
+    let target = folder_id.unwrap_or(1);
+    state
+        .db
+        .write(move |conn| {
+            db::create_feed(conn, &title, &feed_url, target, "watch")?;
+            let feed_id = db::get_feed_id_by_url(conn, &feed_url)?;
+            options.save(conn, feed_id)?;
+            db::save_watch_config(conn, feed_id, &config)?;
+            Ok(feed_id)
         })
-        .map_err(|e| e.to_string())?
-    };
+        .await
+}
 
-    let mut articles = scrape_articles_from_page(&html, url);
-    for a in &mut articles {
-        a.feed_id = feed_id;
-    }
+/// Subscribes to a local file or command. Its output must already be a feed,
+/// so it is read once up front and nothing is created if it doesn't parse.
+async fn add_local_feed(
+    source: LocalSource,
+    folder_id: Option<i64>,
+    options: NewFeedOptions,
+    state: &State<'_, AppState>,
+) -> Result<i64> {
+    let client = state.http();
+    let policy = UrlPolicy::for_feed(options.allow_private_network);
+    let content = read_local_feed(&client, |u| client.get(u, policy), &source).await?;
+    let feed = feed_rs::parser::parse(Cursor::new(content))?;
+    let title = feed
+        .title
+        .map(|t| t.content)
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| source.default_title());
+
+    let feed_url = source.to_url();
+    let feed_type = source.feed_type();
+    let target = folder_id.unwrap_or(1);
+    state
+        .db
+        .write(move |conn| {
+            db::create_feed(conn, &title, &feed_url, target, feed_type)?;
+            let feed_id = db::get_feed_id_by_url(conn, &feed_url)?;
+            options.save(conn, feed_id)?;
+            Ok(feed_id)
+        })
+        .await
+}
 
-    if articles.is_empty() {
-        return Err(format!("No articles found on page: {}", url));
+/// Allows an existing local feed to run on this computer, after a native
+/// confirmation. `false` if the user declines.
+#[tauri::command]
+pub async fn confirm_local_feed(
+    feed_id: i64,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool> {
+    let feed = state
+        .db
+        .read(move |conn| db::get_feed(conn, feed_id))
+        .await?;
+    let Some(source) = LocalSource::parse(&feed.url).filter(|_| is_local_feed(&feed.feed_type))
+    else {
+        return Err(FeedMeeError::InvalidInput("Not a local feed".to_string()));
+    };
+    if !confirm_local_source(&app, &source).await? {
+        return Ok(false);
     }
+    state.local_sources.insert(&feed.url)?;
+    Ok(true)
+}
 
-    let conn = state.db.lock().unwrap();
-    for article in articles {
-        let _ = db::insert_article(&conn, &article);
+/// Lists every feed reachable from `url` so the user can pick one before subscribing.
+#[tauri::command]
+pub async fn discover_feeds(
+    url: String,
+    allow_private_network: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<FeedCandidate>> {
+    // Local files and commands are subscribed to as given
+    if LocalSource::parse(&url).is_some() {
+        return Ok(vec![]);
     }
-
-    Ok(feed_id)
+    let (url, url_auth) = auth::split_url_credentials(&url);
+    let credentials = FeedCredentials::new(&url, url_auth.unwrap_or_default());
+    let policy = UrlPolicy::for_feed(allow_private_network.unwrap_or(false));
+    discovery::discover(&state.http(), policy, credentials.as_ref(), &url).await
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn add_feed(
     url: String,
     folder_id: Option<i64>,
+    auth: Option<FeedAuth>,
+    allow_private_network: Option<bool>,
+    feed_url: Option<String>,
+    watch: Option<WatchConfig>,
+    app: AppHandle,
     state: State<'_, AppState>,
-) -> Result<i64, String> {
-    let client = state.http_client.clone();
-    let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
+) -> Result<i64> {
+    if let Some(source) = LocalSource::parse(&url) {
+        if !confirm_local_source(&app, &source).await? {
+            return Err(FeedMeeError::InvalidInput(
+                "Local feed was not allowed".to_string(),
+            ));
+        }
+        state.local_sources.insert(&source.to_url())?;
+        let options = NewFeedOptions {
+            auth: auth.unwrap_or_default(),
+            allow_private_network: allow_private_network.unwrap_or(false),
+        };
+        let id = add_local_feed(source, folder_id, options, &state).await?;
+        let _ = refresh_feed(id, state).await;
+        return Ok(id);
+    }
 
-    let original_url = response.url().clone();
-    let content_bytes = response.bytes().await.map_err(|e| e.to_string())?;
+    // Credentials typed into the URL are stored separately, never in feeds.url
+    let (url, url_auth) = auth::split_url_credentials(&url);
+    let feed_auth = auth
+        .unwrap_or_default()
+        .merged_onto(url_auth.unwrap_or_default());
+    let credentials = FeedCredentials::new(&url, feed_auth.clone());
+    let options = NewFeedOptions {
+        auth: feed_auth,
+        allow_private_network: allow_private_network.unwrap_or(false),
+    };
 
-    // Try direct RSS parse first
-    let initial_parse = match feed_rs::parser::parse(Cursor::new(content_bytes.clone())) {
-        Ok(f) if !f.entries.is_empty() => Some((f, url.clone())),
+    let client = state.http();
+    let policy = UrlPolicy::for_feed(options.allow_private_network);
+
+    // Watching a page skips discovery; the page itself is what's followed
+    if let Some(config) = watch {
+        watch::validate(&config)?;
+        let request = auth::authorize(client.get(&url, policy)?, credentials.as_ref(), &url);
+        let response = request.send().await?.error_for_status()?;
+        let html = client.read_html(response).await?;
+        let id = add_watch_feed(&url, &html, config, folder_id, options, &state).await?;
+        let _ = refresh_feed(id, state).await;
+        return Ok(id);
+    }
+
+    // A candidate picked from `discover_feeds` replaces the page URL the user typed;
+    // otherwise a platform page (YouTube, GitHub...) becomes its native feed
+    let entered_url = url.clone();
+    let (url, from_platform) = match feed_url {
+        Some(chosen) => (auth::strip_url_credentials(&chosen), false),
+        None => discovery::platform_candidate(&client, policy, credentials.as_ref(), &url)
+            .await
+            .map_or((url, false), |c| (c.url, true)),
+    };
+    let options = options.scoped_to(&entered_url, &url);
+    let get = |u: &str| -> Result<reqwest::RequestBuilder> {
+        Ok(auth::authorize(
+            client.get(u, policy)?,
+            credentials.as_ref(),
+            u,
+        ))
+    };
+    let response = get(&url)?.send().await?;
+
+    let original_url = response.url().clone();
+    let content_type = http::content_type(&response);
+    let content_bytes = client.read_body(response).await?;
+
+    // Try direct RSS parse first. A platform feed may be empty for now (a repo
+    // with no releases yet) and is still the right thing to subscribe to.
+    let feed_bytes = charset::feed_to_utf8(&content_bytes, content_type.as_deref());
+    let initial_parse = match feed_rs::parser::parse(Cursor::new(&feed_bytes)) {
+        Ok(f) if !f.entries.is_empty() || from_platform => Some((f, url.clone())),
         _ => None,
     };
 
@@ -609,59 +1394,27 @@ pub async fn add_feed(
         initial_parse.is_some()
     );
 
+    if initial_parse.is_none() && sitemap::parse(&content_bytes).is_ok() {
+        let id = add_sitemap_feed(&url, folder_id, options, &state).await?;
+        let _ = refresh_feed(id, state).await;
+        return Ok(id);
+    }
+
     let (feed, final_url, feed_type) = if let Some((f, u)) = initial_parse {
         (f, u, "rss".to_string())
     } else {
-        // Try to discover RSS in HTML
-        let discovered_url_str = {
-            let html_content = String::from_utf8_lossy(&content_bytes);
-            debug!(
-                "add_feed: HTML preview (first 1000): {}",
-                &html_content[..html_content.len().min(1000)]
-            );
-            let document = Html::parse_document(&html_content);
-            let feed_types = [
-                "application/rss+xml",
-                "application/atom+xml",
-                "application/feed+json",
-            ];
-            let found = Selector::parse("link").ok().and_then(|sel| {
-                let all_links: Vec<_> = document.select(&sel).collect();
-                debug!("add_feed: found {} <link> tags", all_links.len());
-                for el in &all_links {
-                    let t = el.value().attr("type").unwrap_or("");
-                    let h = el.value().attr("href").unwrap_or("");
-                    if !t.is_empty() {
-                        debug!("add_feed: <link type={:?} href={:?}>", t, h);
-                    }
-                }
-                all_links.into_iter().find_map(|el| {
-                    let t = el.value().attr("type").unwrap_or("");
-                    if feed_types.iter().any(|ft| t.contains(ft)) {
-                        el.value().attr("href").map(|h| h.to_string())
-                    } else {
-                        None
-                    }
-                })
-            });
-
-            found.and_then(|href| {
-                Url::parse(original_url.as_str())
-                    .and_then(|base| base.join(&href))
-                    .ok()
-                    .map(|u| u.to_string())
-            })
-        };
+        let page_html =
+            charset::decode_html(&content_bytes, content_type.as_deref(), &original_url);
+        let discovered_url_str = discovery::feed_links(&page_html, &original_url)
+            .into_iter()
+            .next()
+            .map(|link| link.url);
 
         if let Some(new_url) = discovered_url_str {
             debug!("add_feed: discovered RSS url={}", new_url);
-            let resp = client
-                .get(&new_url)
-                .send()
-                .await
-                .map_err(|e| e.to_string())?;
-            let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
-            match feed_rs::parser::parse(Cursor::new(bytes.clone())) {
+            let resp = get(&new_url)?.send().await?;
+            let bytes = client.read_feed(resp).await?;
+            match feed_rs::parser::parse(Cursor::new(&bytes)) {
                 Ok(f) => {
                     info!(
                         "add_feed: RSS parse ok, {} entries, title={:?}",
@@ -673,7 +1426,8 @@ pub async fn add_feed(
                             "add_feed: RSS feed is empty, falling back to website scraping for {}",
                             url
                         );
-                        return add_website_feed(&url, &content_bytes, folder_id, &state).await;
+                        return add_website_feed(&url, &page_html, folder_id, options, &state)
+                            .await;
                     }
                     (f, new_url, "rss".to_string())
                 },
@@ -682,12 +1436,12 @@ pub async fn add_feed(
                     // Log first 500 bytes of response for diagnosis
                     let preview = String::from_utf8_lossy(&bytes[..bytes.len().min(500)]);
                     error!("add_feed: response preview: {}", preview);
-                    return add_website_feed(&url, &content_bytes, folder_id, &state).await;
+                    return add_website_feed(&url, &page_html, folder_id, options, &state).await;
                 },
             }
         } else {
             debug!("add_feed: no RSS found, treating as website");
-            return add_website_feed(&url, &content_bytes, folder_id, &state).await;
+            return add_website_feed(&url, &page_html, folder_id, options, &state).await;
         }
     };
 
@@ -696,16 +1450,17 @@ pub async fn add_feed(
         .map(|t| t.content)
         .unwrap_or_else(|| "Untitled Feed".to_string());
 
-    let id = {
-        let conn = state.db.lock().unwrap();
-        let target = folder_id.unwrap_or(1);
-        db::create_feed(&conn, &title, &final_url, target, &feed_type)
-            .map_err(|e| e.to_string())?;
-        conn.query_row("SELECT id FROM feeds WHERE url = ?1", [&final_url], |row| {
-            row.get(0)
+    let options = options.scoped_to(&entered_url, &final_url);
+    let target = folder_id.unwrap_or(1);
+    let id = state
+        .db
+        .write(move |conn| {
+            db::create_feed(conn, &title, &final_url, target, &feed_type)?;
+            let id = db::get_feed_id_by_url(conn, &final_url)?;
+            options.save(conn, id)?;
+            Ok(id)
         })
-        .map_err(|e| e.to_string())?
-    };
+        .await?;
 
     let _ = refresh_feed(id, state).await;
 
@@ -713,43 +1468,145 @@ pub async fn add_feed(
 }
 
 #[tauri::command]
-pub fn rename_folder(id: i64, new_name: String, state: State<'_, AppState>) -> Result<(), String> {
-    let conn = state.db.lock().unwrap();
-    db::rename_folder(&conn, id, &new_name).map_err(|e| e.to_string())
+pub async fn rename_folder(id: i64, new_name: String, state: State<'_, AppState>) -> Result<()> {
+    state
+        .db
+        .write(move |conn| db::rename_folder(conn, id, &new_name))
+        .await
 }
 
 #[tauri::command]
-pub fn rename_feed(id: i64, new_name: String, state: State<'_, AppState>) -> Result<(), String> {
-    let conn = state.db.lock().unwrap();
-    db::rename_feed(&conn, id, &new_name).map_err(|e| e.to_string())
+pub async fn rename_feed(id: i64, new_name: String, state: State<'_, AppState>) -> Result<()> {
+    state
+        .db
+        .write(move |conn| db::rename_feed(conn, id, &new_name))
+        .await
 }
 
 #[tauri::command]
-pub fn delete_feed(id: i64, state: State<'_, AppState>) -> Result<(), String> {
-    let conn = state.db.lock().unwrap();
-    db::delete_feed(&conn, id).map_err(|e| e.to_string())
+pub async fn delete_feed(id: i64, state: State<'_, AppState>) -> Result<()> {
+    state.db.write(move |conn| db::trash_feed(conn, id)).await
 }
 
 #[tauri::command]
-pub fn delete_folder(id: i64, state: State<'_, AppState>) -> Result<(), String> {
-    let conn = state.db.lock().unwrap();
-    db::delete_folder(&conn, id).map_err(|e| e.to_string())
+pub async fn delete_folder(id: i64, state: State<'_, AppState>) -> Result<()> {
+    state.db.write(move |conn| db::trash_folder(conn, id)).await
 }
 
 #[tauri::command]
-pub fn move_feed(feed_id: i64, folder_id: i64, state: State<'_, AppState>) -> Result<(), String> {
-    let conn = state.db.lock().unwrap();
-    db::move_feed(&conn, feed_id, folder_id).map_err(|e| e.to_string())
+pub async fn get_trash(state: State<'_, AppState>) -> Result<Vec<TrashItem>> {
+    state.db.read(db::get_trash).await
 }
 
 #[tauri::command]
-pub fn search_articles(
-    query: String,
-    limit: usize,
-    offset: usize,
-    sort_desc: bool,
+pub async fn restore_from_trash(
+    item_type: String,
+    id: i64,
     state: State<'_, AppState>,
-) -> Result<Vec<crate::models::Article>, String> {
-    let conn = state.db.lock().unwrap();
-    db::search_articles(&conn, &query, limit, offset, !sort_desc).map_err(|e| e.to_string())
+) -> Result<()> {
+    state
+        .db
+        .write(move |conn| match item_type.as_str() {
+            "feed" => db::restore_feed(conn, id),
+            "folder" => db::restore_folder(conn, id),
+            _ => Err(FeedMeeError::InvalidInput(format!(
+                "Invalid trash item type: {}",
+                item_type
+            ))),
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn empty_trash(state: State<'_, AppState>) -> Result<usize> {
+    let keep_saved = state.settings.lock().unwrap().keep_saved_on_delete;
+    state
+        .db
+        .write(move |conn| db::purge_trash(conn, i64::MAX, keep_saved))
+        .await
+}
+
+#[tauri::command]
+pub async fn get_db_health(state: State<'_, AppState>) -> Result<DbHealth> {
+    state.db.read(maintenance::get_health).await
+}
+
+#[tauri::command]
+pub async fn compact_database(state: State<'_, AppState>) -> Result<DbHealth> {
+    state.db.write(|conn| maintenance::compact(conn)).await
+}
+
+#[tauri::command]
+pub async fn list_backups(state: State<'_, AppState>) -> Result<Vec<BackupInfo>> {
+    backup::list_backups(&backup::backup_dir(state.db.path()))
+}
+
+#[tauri::command]
+pub async fn create_backup(state: State<'_, AppState>) -> Result<BackupInfo> {
+    let dir = backup::backup_dir(state.db.path());
+    let keep = state.settings.lock().unwrap().backup_keep_count as usize;
+    state
+        .db
+        .read(move |conn| {
+            let info = backup::create_backup(conn, &dir)?;
+            backup::rotate_backups(&dir, keep.max(1))?;
+            Ok(info)
+        })
+        .await
+}
+
+#[tauri::command]
+pub async fn restore_backup(name: String, state: State<'_, AppState>) -> Result<()> {
+    let dir = backup::backup_dir(state.db.path());
+    let path = backup::resolve_backup(&dir, &name)?;
+    state
+        .db
+        .write(move |conn| {
+            // Snapshot the current state first so a restore can itself be undone.
+            backup::create_backup(conn, &dir)?;
+            backup::restore_backup(conn, &path)
+        })
+        .await?;
+    // Local feeds in the restored database must be allowed again
+    state.local_sources.clear()
+}
+
+/// Copies a backup to a location picked in a save dialog. `false` if the user cancels.
+#[tauri::command]
+pub async fn export_backup(
+    name: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<bool> {
+    let src = backup::resolve_backup(&backup::backup_dir(state.db.path()), &name)?;
+    let Some(path) = pick_save_path(&app, "FeedMee Backup", &["sqlite"], &name).await? else {
+        return Ok(false);
+    };
+    fs::copy(src, &path)?;
+    info!("Exported backup {} to {:?}", name, path);
+    Ok(true)
+}
+
+/// Copies a backup picked in an open dialog into the backups folder. `None` if
+/// the user cancels.
+#[tauri::command]
+pub async fn import_backup(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<BackupInfo>> {
+    let Some(path) = pick_open_path(&app, "FeedMee Backup", &["sqlite"]).await? else {
+        return Ok(None);
+    };
+    let dir = backup::backup_dir(state.db.path());
+    tauri::async_runtime::spawn_blocking(move || backup::import_backup(&path, &dir))
+        .await?
+        .map(Some)
+}
+
+#[tauri::command]
+pub async fn move_feed(feed_id: i64, folder_id: i64, state: State<'_, AppState>) -> Result<()> {
+    state
+        .db
+        .write(move |conn| db::move_feed(conn, feed_id, folder_id))
+        .await
 }
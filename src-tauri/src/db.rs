@@ -1,12 +1,17 @@
-use crate::models::{Article, Feed, Folder};
-use log::{debug, info};
-use rusqlite::{Connection, Result, params};
+use crate::error::{FeedMeeError, Result};
+use crate::models::{
+    Article, ArticlePage, ArticleQuery, ArticleSort, Feed, FeedAuth, FeedSettings, Folder,
+    ScrapeConfig, TrashItem, WatchConfig,
+};
+use log::{debug, info, warn};
+use rusqlite::types::Value;
+use rusqlite::{Connection, params, params_from_iter};
 use rusqlite_migration::{M, Migrations};
 
 // Each entry is an immutable, append-only migration.
 // Never edit a past migration - add a new one instead.
-fn migrations() -> Migrations<'static> {
-    Migrations::new(vec![
+fn migration_steps() -> Vec<M<'static>> {
+    vec![
         // v1: initial schema
         M::up(
             "CREATE TABLE IF NOT EXISTS folders (
@@ -37,20 +42,151 @@ fn migrations() -> Migrations<'static> {
             );
             INSERT OR IGNORE INTO folders (id, name) VALUES (1, 'Uncategorized');",
         ),
-    ])
+        // v2: covering indexes for article lists, trigger-maintained unread counters
+        M::up(
+            "CREATE INDEX IF NOT EXISTS idx_articles_feed_ts ON articles (feed_id, timestamp, id);
+            CREATE INDEX IF NOT EXISTS idx_articles_ts ON articles (timestamp, id);
+            CREATE INDEX IF NOT EXISTS idx_articles_unread ON articles (feed_id) WHERE is_read = 0;
+            CREATE INDEX IF NOT EXISTS idx_articles_saved ON articles (timestamp, id) WHERE is_saved = 1;
+            CREATE INDEX IF NOT EXISTS idx_feeds_folder ON feeds (folder_id);
+
+            ALTER TABLE feeds ADD COLUMN unread_count INTEGER NOT NULL DEFAULT 0;
+            UPDATE feeds SET unread_count =
+                (SELECT COUNT(*) FROM articles a WHERE a.feed_id = feeds.id AND a.is_read = 0);
+
+            CREATE TRIGGER IF NOT EXISTS trg_articles_unread_insert
+            AFTER INSERT ON articles WHEN NEW.is_read = 0
+            BEGIN
+                UPDATE feeds SET unread_count = unread_count + 1 WHERE id = NEW.feed_id;
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_articles_unread_delete
+            AFTER DELETE ON articles WHEN OLD.is_read = 0
+            BEGIN
+                UPDATE feeds SET unread_count = unread_count - 1 WHERE id = OLD.feed_id;
+            END;
+            CREATE TRIGGER IF NOT EXISTS trg_articles_unread_update
+            AFTER UPDATE OF is_read, feed_id ON articles
+            WHEN OLD.is_read != NEW.is_read OR OLD.feed_id != NEW.feed_id
+            BEGIN
+                UPDATE feeds SET unread_count = unread_count - (OLD.is_read = 0)
+                WHERE id = OLD.feed_id;
+                UPDATE feeds SET unread_count = unread_count + (NEW.is_read = 0)
+                WHERE id = NEW.feed_id;
+            END;",
+        ),
+        // v3: first-seen time, so undated entries no longer sort to 1970
+        M::up(
+            "ALTER TABLE articles ADD COLUMN fetched_at INTEGER NOT NULL DEFAULT 0;
+            UPDATE articles SET fetched_at = CASE
+                WHEN timestamp > 0 THEN MIN(timestamp, CAST(strftime('%s', 'now') AS INTEGER))
+                ELSE CAST(strftime('%s', 'now') AS INTEGER)
+            END;
+            UPDATE articles SET timestamp = fetched_at
+            WHERE timestamp IS NULL OR timestamp <= 0 OR timestamp > fetched_at;
+            CREATE INDEX IF NOT EXISTS idx_articles_fetched ON articles (fetched_at, id);",
+        ),
+        // v4: soft-delete trash for feeds and folders
+        M::up(
+            "ALTER TABLE feeds ADD COLUMN deleted_at INTEGER;
+            ALTER TABLE folders ADD COLUMN deleted_at INTEGER;
+            CREATE INDEX IF NOT EXISTS idx_feeds_deleted ON feeds (deleted_at)
+                WHERE deleted_at IS NOT NULL;",
+        ),
+        // v5: key/value store for maintenance bookkeeping
+        M::up(
+            "CREATE TABLE IF NOT EXISTS meta (
+                key   TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );",
+        ),
+        // v6: per-feed and per-folder setting overrides; NULL columns inherit
+        M::up(
+            "CREATE TABLE IF NOT EXISTS feed_settings (
+                id                       INTEGER PRIMARY KEY,
+                feed_id                  INTEGER UNIQUE REFERENCES feeds (id) ON DELETE CASCADE,
+                folder_id                INTEGER UNIQUE REFERENCES folders (id) ON DELETE CASCADE,
+                refresh_interval_minutes INTEGER,
+                retention_days           INTEGER,
+                full_text                BOOLEAN,
+                user_agent               TEXT,
+                reader_mode              BOOLEAN,
+                mark_read_on_open        BOOLEAN,
+                notifications_muted      BOOLEAN,
+                sort_order               TEXT,
+                CHECK ((feed_id IS NULL) != (folder_id IS NULL))
+            );
+            ALTER TABLE feeds ADD COLUMN last_refreshed_at INTEGER NOT NULL DEFAULT 0;",
+        ),
+        // v7: per-feed HTTP credentials, kept out of feeds.url so they never reach OPML
+        M::up(
+            "CREATE TABLE IF NOT EXISTS feed_auth (
+                feed_id      INTEGER PRIMARY KEY REFERENCES feeds (id) ON DELETE CASCADE,
+                username     TEXT,
+                password     TEXT,
+                bearer_token TEXT,
+                cookies      TEXT,
+                headers      TEXT
+            );",
+        ),
+        // v8: per-feed opt-in to fetching from private network addresses
+        M::up("ALTER TABLE feed_settings ADD COLUMN allow_private_network BOOLEAN;"),
+        // v9: CSS-selector scraping rules for website feeds
+        M::up(
+            "CREATE TABLE IF NOT EXISTS feed_scrape_config (
+                feed_id          INTEGER PRIMARY KEY REFERENCES feeds (id) ON DELETE CASCADE,
+                item_selector    TEXT NOT NULL,
+                title_selector   TEXT,
+                link_selector    TEXT,
+                date_selector    TEXT,
+                summary_selector TEXT,
+                include_patterns TEXT NOT NULL DEFAULT '[]',
+                exclude_patterns TEXT NOT NULL DEFAULT '[]'
+            );",
+        ),
+        // v10: opt-in fetch of article pages for their structured data
+        M::up(
+            "ALTER TABLE feed_scrape_config
+                ADD COLUMN fetch_article_pages BOOLEAN NOT NULL DEFAULT 0;",
+        ),
+        // v11: watch feeds; the last snapshot is kept to diff the next one against
+        M::up(
+            "CREATE TABLE IF NOT EXISTS feed_watch (
+                feed_id           INTEGER PRIMARY KEY REFERENCES feeds (id) ON DELETE CASCADE,
+                selector          TEXT,
+                ignore_selectors  TEXT NOT NULL DEFAULT '[]',
+                min_changed_chars INTEGER NOT NULL DEFAULT 10,
+                last_text         TEXT
+            );",
+        ),
+        // v12: why the last refresh failed, e.g. a feed command's stderr
+        M::up("ALTER TABLE feeds ADD COLUMN last_error TEXT;"),
+    ]
+}
+
+fn migrations() -> Migrations<'static> {
+    Migrations::new(migration_steps())
+}
+
+/// The `user_version` a fully migrated database has.
+pub fn schema_version() -> i64 {
+    migration_steps().len() as i64
 }
 
-pub fn init_db(conn: &mut Connection) -> Result<(), Box<dyn std::error::Error>> {
+pub fn init_db(conn: &mut Connection) -> Result<()> {
     info!("Initializing database");
 
+    // auto_vacuum only takes effect on a brand-new file; existing databases
+    // are converted later by the maintenance task.
     conn.execute_batch(
-        "PRAGMA journal_mode = WAL;
+        "PRAGMA auto_vacuum = INCREMENTAL;
+         PRAGMA journal_mode = WAL;
          PRAGMA synchronous = NORMAL;
          PRAGMA foreign_keys = ON;",
     )?;
 
     let m = migrations();
     m.to_latest(conn)?;
+    migrate_url_credentials(conn)?;
 
     let version: i64 = conn.query_row("PRAGMA user_version", [], |r| r.get(0))?;
     info!("Database schema at version {}", version);
@@ -58,10 +194,20 @@ pub fn init_db(conn: &mut Connection) -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
-pub fn run_vacuum(conn: &Connection) -> Result<()> {
-    info!("Running database VACUUM...");
-    conn.execute("VACUUM", [])?;
-    info!("Database VACUUM completed");
+pub fn get_meta(conn: &Connection, key: &str) -> Result<Option<String>> {
+    match conn.query_row("SELECT value FROM meta WHERE key = ?1", [key], |r| r.get(0)) {
+        Ok(value) => Ok(Some(value)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn set_meta(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO meta (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    )?;
     Ok(())
 }
 
@@ -70,15 +216,16 @@ pub fn run_vacuum(conn: &Connection) -> Result<()> {
 pub fn get_folders_with_feeds(conn: &Connection) -> Result<Vec<Folder>> {
     debug!("Querying folders with feeds");
 
-    let mut folder_stmt =
-        conn.prepare("SELECT id, name FROM folders ORDER BY name COLLATE NOCASE")?;
+    let mut folder_stmt = conn.prepare(
+        "SELECT id, name FROM folders WHERE deleted_at IS NULL ORDER BY name COLLATE NOCASE",
+    )?;
 
     let mut feed_stmt = conn.prepare(
-        "SELECT f.id, f.name, f.url, f.folder_id, f.has_error, f.feed_type, f.content_hash,
-                (SELECT COUNT(*) FROM articles a WHERE a.feed_id = f.id AND a.is_read = 0) AS unread_count
-         FROM feeds f
-         WHERE f.folder_id = ?1
-         ORDER BY f.name COLLATE NOCASE",
+        "SELECT id, name, url, folder_id, has_error, feed_type, content_hash, unread_count,
+                last_error
+         FROM feeds
+         WHERE folder_id = ?1 AND deleted_at IS NULL
+         ORDER BY name COLLATE NOCASE",
     )?;
 
     let folders = folder_stmt
@@ -96,6 +243,7 @@ pub fn get_folders_with_feeds(conn: &Connection) -> Result<Vec<Folder>> {
                         feed_type: r.get(5).unwrap_or_else(|_| "rss".to_string()),
                         content_hash: r.get(6).unwrap_or_default(),
                         unread_count: r.get(7)?,
+                        last_error: r.get(8)?,
                     })
                 })
                 .and_then(|rows| rows.collect());
@@ -105,105 +253,174 @@ pub fn get_folders_with_feeds(conn: &Connection) -> Result<Vec<Folder>> {
                 feeds: feeds.unwrap_or_default(),
             })
         })?
-        .collect::<Result<Vec<Folder>>>()?;
+        .collect::<rusqlite::Result<Vec<Folder>>>()?;
 
     Ok(folders)
 }
 
-pub fn get_articles_for_feed(
-    conn: &Connection,
-    feed_id: i64,
-    limit: usize,
-    offset: usize,
-    sort_asc: bool,
-) -> Result<Vec<Article>> {
-    let order = if sort_asc { "ASC" } else { "DESC" };
-    let sql = format!(
-        "SELECT id, feed_id, title, author, summary, url, timestamp, is_read, is_saved
-         FROM articles WHERE feed_id = ?1
-         ORDER BY timestamp {} LIMIT ?2 OFFSET ?3",
-        order
-    );
-    let mut stmt = conn.prepare(&sql)?;
-    map_articles(&mut stmt, params![feed_id, limit as i64, offset as i64])
+/// Position in an article list, keyed on the active sort column plus `id` so pages
+/// stay stable when new rows are inserted mid-scroll. Handed to the frontend as an
+/// opaque string.
+#[derive(Clone, Debug)]
+pub struct PageCursor {
+    pub sort: ArticleSort,
+    pub key: Value,
+    pub id: i64,
 }
 
-pub fn get_articles_for_folder(
-    conn: &Connection,
-    folder_id: i64,
-    limit: usize,
-    offset: usize,
-    sort_asc: bool,
-) -> Result<Vec<Article>> {
-    let order = if sort_asc { "ASC" } else { "DESC" };
+impl PageCursor {
+    fn from_article(sort: ArticleSort, article: &Article, feed_name: &str) -> Self {
+        let key = match sort {
+            ArticleSort::Date => Value::Integer(article.timestamp),
+            ArticleSort::Added => Value::Integer(article.id),
+            ArticleSort::Title => Value::Text(article.title.clone()),
+            ArticleSort::Feed => Value::Text(feed_name.to_string()),
+        };
+        Self {
+            sort,
+            key,
+            id: article.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let key = match &self.key {
+            Value::Integer(i) => i.to_string(),
+            Value::Text(t) => t.clone(),
+            _ => String::new(),
+        };
+        format!("{}:{}:{}", self.sort.as_str(), self.id, key)
+    }
+
+    pub fn decode(cursor: &str, sort: ArticleSort) -> Result<Self> {
+        let invalid = || FeedMeeError::InvalidInput(format!("Invalid cursor: {}", cursor));
+        let mut parts = cursor.splitn(3, ':');
+        let (Some(cursor_sort), Some(id), Some(key)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        if cursor_sort != sort.as_str() {
+            return Err(invalid());
+        }
+        let id = id.parse().map_err(|_| invalid())?;
+        let key = match sort {
+            ArticleSort::Date | ArticleSort::Added => {
+                Value::Integer(key.parse().map_err(|_| invalid())?)
+            },
+            ArticleSort::Title | ArticleSort::Feed => Value::Text(key.to_string()),
+        };
+        Ok(Self { sort, key, id })
+    }
+}
+
+/// Single entry point for every article list: feed, folder, Latest, Saved and search.
+/// Filters combine with AND; results are keyset-paginated on `(sort key, id)`.
+pub fn query_articles(conn: &Connection, query: &ArticleQuery) -> Result<ArticlePage> {
+    let mut conditions: Vec<&str> = vec!["f.deleted_at IS NULL"];
+    let mut args: Vec<Value> = Vec::new();
+
+    if let Some(feed_id) = query.feed_id {
+        conditions.push("a.feed_id = ?");
+        args.push(Value::Integer(feed_id));
+    }
+    if let Some(folder_id) = query.folder_id {
+        conditions.push("f.folder_id = ?");
+        args.push(Value::Integer(folder_id));
+    }
+    if let Some(search) = query.search.as_deref().filter(|s| !s.is_empty()) {
+        conditions.push("a.title LIKE ?");
+        args.push(Value::Text(format!("%{}%", search)));
+    }
+    if let Some(author) = query.author.as_deref().filter(|s| !s.is_empty()) {
+        conditions.push("a.author = ? COLLATE NOCASE");
+        args.push(Value::Text(author.to_string()));
+    }
+    let date_column = query.date_field.column();
+    let since_clause = format!("{} > ?", date_column);
+    let until_clause = format!("{} <= ?", date_column);
+    if let Some(since) = query.since {
+        conditions.push(&since_clause);
+        args.push(Value::Integer(since));
+    }
+    if let Some(until) = query.until {
+        conditions.push(&until_clause);
+        args.push(Value::Integer(until));
+    }
+    if query.unread_only {
+        conditions.push("a.is_read = 0");
+    }
+    if query.saved_only {
+        conditions.push("a.is_saved = 1");
+    }
+
+    let sort_key = query.sort.column();
+    let (order, cmp) = if query.sort_asc.unwrap_or(false) {
+        ("ASC", ">")
+    } else {
+        ("DESC", "<")
+    };
+    let keyset;
+    if let Some(cursor) = query.cursor.as_deref() {
+        let cursor = PageCursor::decode(cursor, query.sort)?;
+        keyset = format!("({}, a.id) {} (?, ?)", sort_key, cmp);
+        conditions.push(&keyset);
+        args.push(cursor.key);
+        args.push(Value::Integer(cursor.id));
+    }
+    // Fetch one extra row to learn whether another page exists
+    let limit = query.limit.max(1);
+    args.push(Value::Integer(limit as i64 + 1));
+
+    let where_clause = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    };
     let sql = format!(
-        "SELECT a.id, a.feed_id, a.title, a.author, a.summary, a.url, a.timestamp, a.is_read, a.is_saved
+        "SELECT a.id, a.feed_id, a.title, a.author, a.summary, a.url, a.timestamp, a.is_read, a.is_saved,
+                a.fetched_at, f.name
          FROM articles a
          JOIN feeds f ON a.feed_id = f.id
-         WHERE f.folder_id = ?1
-         ORDER BY a.timestamp {} LIMIT ?2 OFFSET ?3",
-        order
+         {}
+         ORDER BY {} {order}, a.id {order} LIMIT ?",
+        where_clause, sort_key,
     );
-    let mut stmt = conn.prepare(&sql)?;
-    map_articles(&mut stmt, params![folder_id, limit as i64, offset as i64])
-}
+    debug!("query_articles: {:?}", query);
 
-pub fn get_latest_articles(
-    conn: &Connection,
-    cutoff_timestamp: i64,
-    limit: usize,
-    offset: usize,
-    sort_asc: bool,
-) -> Result<Vec<Article>> {
-    let order = if sort_asc { "ASC" } else { "DESC" };
-    let sql = format!(
-        "SELECT id, feed_id, title, author, summary, url, timestamp, is_read, is_saved
-         FROM articles WHERE timestamp > ?1
-         ORDER BY timestamp {} LIMIT ?2 OFFSET ?3",
-        order
-    );
-    let mut stmt = conn.prepare(&sql)?;
-    map_articles(
-        &mut stmt,
-        params![cutoff_timestamp, limit as i64, offset as i64],
-    )
+    let mut stmt = conn.prepare_cached(&sql)?;
+    let mut rows = stmt
+        .query_map(params_from_iter(args), |row| {
+            Ok((row_to_article(row)?, row.get::<_, String>(10)?))
+        })?
+        .collect::<rusqlite::Result<Vec<(Article, String)>>>()?;
+
+    let next_cursor = if rows.len() > limit {
+        rows.truncate(limit);
+        rows.last()
+            .map(|(a, feed_name)| PageCursor::from_article(query.sort, a, feed_name).encode())
+    } else {
+        None
+    };
+    Ok(ArticlePage {
+        articles: rows.into_iter().map(|(a, _)| a).collect(),
+        next_cursor,
+        sort_asc: query.sort_asc.unwrap_or(false),
+    })
 }
 
-pub fn get_saved_articles(
-    conn: &Connection,
-    limit: usize,
-    offset: usize,
-    sort_asc: bool,
-) -> Result<Vec<Article>> {
-    let order = if sort_asc { "ASC" } else { "DESC" };
-    let sql = format!(
-        "SELECT id, feed_id, title, author, summary, url, timestamp, is_read, is_saved
-         FROM articles WHERE is_saved = 1
-         ORDER BY timestamp {} LIMIT ?1 OFFSET ?2",
-        order
-    );
-    let mut stmt = conn.prepare(&sql)?;
-    map_articles(&mut stmt, params![limit as i64, offset as i64])
-}
-
-fn map_articles(
-    stmt: &mut rusqlite::Statement,
-    params: impl rusqlite::Params,
-) -> Result<Vec<Article>> {
-    stmt.query_map(params, |row| {
-        Ok(Article {
-            id: row.get(0)?,
-            feed_id: row.get(1)?,
-            title: row.get(2)?,
-            author: row.get(3).unwrap_or_default(),
-            summary: row.get(4).unwrap_or_default(),
-            url: row.get(5)?,
-            timestamp: row.get(6)?,
-            is_read: row.get(7)?,
-            is_saved: row.get(8)?,
-        })
-    })?
-    .collect::<Result<Vec<Article>>>()
+fn row_to_article(row: &rusqlite::Row) -> rusqlite::Result<Article> {
+    Ok(Article {
+        id: row.get(0)?,
+        feed_id: row.get(1)?,
+        title: row.get(2)?,
+        author: row.get(3).unwrap_or_default(),
+        summary: row.get(4).unwrap_or_default(),
+        url: row.get(5)?,
+        timestamp: row.get(6)?,
+        is_read: row.get(7)?,
+        is_saved: row.get(8)?,
+        fetched_at: row.get(9)?,
+    })
 }
 
 pub fn get_feed_url(conn: &Connection, feed_id: i64) -> Result<String> {
@@ -212,37 +429,61 @@ pub fn get_feed_url(conn: &Connection, feed_id: i64) -> Result<String> {
         params![feed_id],
         |r| r.get(0),
     )
+    .map_err(|e| feed_not_found(e, feed_id))
+}
+
+pub fn get_feed_id_by_url(conn: &Connection, url: &str) -> Result<i64> {
+    conn.query_row("SELECT id FROM feeds WHERE url = ?1", [url], |r| r.get(0))
+        .map_err(Into::into)
 }
 
 pub fn get_feed_unread_count(conn: &Connection, feed_id: i64) -> Result<i64> {
     conn.query_row(
-        "SELECT COUNT(*) FROM articles WHERE feed_id = ?1 AND is_read = 0",
+        "SELECT unread_count FROM feeds WHERE id = ?1",
         params![feed_id],
         |r| r.get(0),
     )
+    .map_err(|e| feed_not_found(e, feed_id))
 }
+
 pub fn get_feed(conn: &Connection, feed_id: i64) -> Result<Feed> {
     conn.query_row(
-        "SELECT id, name, url, folder_id, has_error, feed_type, content_hash FROM feeds WHERE id = ?1",
+        "SELECT id, name, url, folder_id, has_error, feed_type, content_hash, unread_count,
+                last_error
+         FROM feeds WHERE id = ?1",
         params![feed_id],
-        |r| Ok(Feed {
-            id: r.get(0)?,
-            name: r.get(1)?,
-            url: r.get(2)?,
-            folder_id: r.get(3)?,
-            has_error: r.get::<_, bool>(4).unwrap_or(false),
-            feed_type: r.get(5).unwrap_or_else(|_| "rss".to_string()),
-            content_hash: r.get(6).unwrap_or_default(),
-            unread_count: 0,
-        }),
+        |r| {
+            Ok(Feed {
+                id: r.get(0)?,
+                name: r.get(1)?,
+                url: r.get(2)?,
+                folder_id: r.get(3)?,
+                has_error: r.get::<_, bool>(4).unwrap_or(false),
+                feed_type: r.get(5).unwrap_or_else(|_| "rss".to_string()),
+                content_hash: r.get(6).unwrap_or_default(),
+                unread_count: r.get(7)?,
+                last_error: r.get(8)?,
+            })
+        },
     )
+    .map_err(|e| feed_not_found(e, feed_id))
+}
+
+fn feed_not_found(e: rusqlite::Error, feed_id: i64) -> FeedMeeError {
+    match e {
+        rusqlite::Error::QueryReturnedNoRows => {
+            FeedMeeError::NotFound(format!("Feed {} not found", feed_id))
+        },
+        e => e.into(),
+    }
 }
 
 // --- Write Operations ---
 
 pub fn create_folder(conn: &Connection, name: &str) -> Result<i64> {
     conn.execute(
-        "INSERT OR IGNORE INTO folders (name) VALUES (?1)",
+        "INSERT INTO folders (name) VALUES (?1)
+         ON CONFLICT(name) DO UPDATE SET deleted_at = NULL",
         params![name],
     )?;
     conn.query_row(
@@ -250,6 +491,7 @@ pub fn create_folder(conn: &Connection, name: &str) -> Result<i64> {
         params![name],
         |r| r.get(0),
     )
+    .map_err(Into::into)
 }
 
 pub fn create_feed(
@@ -261,20 +503,66 @@ pub fn create_feed(
 ) -> Result<()> {
     conn.execute(
         "INSERT INTO feeds (name, url, folder_id, has_error, feed_type) VALUES (?1, ?2, ?3, 0, ?4)
-         ON CONFLICT(url) DO UPDATE SET feed_type = excluded.feed_type",
+         ON CONFLICT(url) DO UPDATE SET feed_type = excluded.feed_type, deleted_at = NULL",
         params![name, url, folder_id, feed_type],
     )?;
     Ok(())
 }
 
-pub fn update_feed_error(conn: &Connection, feed_id: i64, has_error: bool) -> Result<()> {
+/// A failed refresh still counts as an attempt, so erroring feeds wait out their
+/// interval like any other instead of being retried on every tick.
+/// `error` is the message shown for the feed; `None` clears it.
+pub fn update_feed_error(conn: &Connection, feed_id: i64, error: Option<&str>) -> Result<()> {
     conn.execute(
-        "UPDATE feeds SET has_error = ?1 WHERE id = ?2",
-        params![has_error, feed_id],
+        "UPDATE feeds SET has_error = ?1, last_error = ?2, last_refreshed_at = ?3 WHERE id = ?4",
+        params![
+            error.is_some(),
+            error,
+            chrono::Utc::now().timestamp(),
+            feed_id
+        ],
     )?;
     Ok(())
 }
 
+/// Drops unsaved articles first seen before `older_than` that are no longer in the
+/// feed's current entry set; dropping ones still listed would just re-add them.
+pub fn prune_articles(
+    conn: &Connection,
+    feed_id: i64,
+    older_than: i64,
+    current_urls: &[String],
+) -> Result<usize> {
+    let placeholders = vec!["?"; current_urls.len()].join(", ");
+    let sql = format!(
+        "DELETE FROM articles
+         WHERE feed_id = ? AND is_saved = 0 AND fetched_at < ? AND url NOT IN ({})",
+        placeholders
+    );
+    let mut args = vec![Value::Integer(feed_id), Value::Integer(older_than)];
+    args.extend(current_urls.iter().cloned().map(Value::Text));
+    let removed = conn.execute(&sql, params_from_iter(args))?;
+    if removed > 0 {
+        debug!("prune_articles: feed_id={}, removed={}", feed_id, removed);
+    }
+    Ok(removed)
+}
+
+/// URLs from `urls` already stored, so refresh can skip work for known entries.
+pub fn existing_article_urls(
+    conn: &Connection,
+    urls: &[String],
+) -> Result<std::collections::HashSet<String>> {
+    let mut stmt = conn.prepare_cached("SELECT 1 FROM articles WHERE url = ?1")?;
+    let mut existing = std::collections::HashSet::new();
+    for url in urls {
+        if stmt.exists([url])? {
+            existing.insert(url.clone());
+        }
+    }
+    Ok(existing)
+}
+
 pub fn update_feed_content_hash(conn: &Connection, feed_id: i64, content_hash: &str) -> Result<()> {
     conn.execute(
         "UPDATE feeds SET content_hash = ?1 WHERE id = ?2",
@@ -283,12 +571,49 @@ pub fn update_feed_content_hash(conn: &Connection, feed_id: i64, content_hash: &
     Ok(())
 }
 
-pub fn insert_article(conn: &Connection, article: &Article) -> Result<usize> {
-    let inserted = conn.execute(
-        "INSERT OR IGNORE INTO articles (feed_id, title, author, summary, url, timestamp, is_read, is_saved)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, 0)",
-        params![article.feed_id, article.title, article.author, article.summary, article.url, article.timestamp],
-    )?;
+/// Display date for a newly seen entry: its own date (published, else updated) if it
+/// has one, otherwise the first-seen time. Future dates are clamped to first-seen.
+pub fn display_timestamp(entry_timestamp: i64, fetched_at: i64) -> i64 {
+    if entry_timestamp <= 0 {
+        fetched_at
+    } else {
+        entry_timestamp.min(fetched_at)
+    }
+}
+
+/// Writes a refreshed entry set for one feed in a single transaction and clears
+/// the feed's error flag. Any failure rolls the whole batch back.
+pub fn ingest_articles(conn: &mut Connection, feed_id: i64, articles: &[Article]) -> Result<usize> {
+    let fetched_at = chrono::Utc::now().timestamp();
+    let tx = conn.transaction()?;
+    let mut inserted = 0;
+    {
+        let mut insert = tx.prepare_cached(
+            "INSERT OR IGNORE INTO articles
+                (feed_id, title, author, summary, url, timestamp, fetched_at, is_read, is_saved)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0, 0)",
+        )?;
+        for article in articles {
+            inserted += insert.execute(params![
+                feed_id,
+                article.title,
+                article.author,
+                article.summary,
+                article.url,
+                display_timestamp(article.timestamp, fetched_at),
+                fetched_at
+            ])?;
+        }
+        tx.prepare_cached(
+            "UPDATE feeds SET has_error = 0, last_error = NULL, last_refreshed_at = ?2 WHERE id = ?1",
+        )?
+            .execute(params![feed_id, fetched_at])?;
+    }
+    tx.commit()?;
+    debug!(
+        "ingest_articles: feed_id={}, inserted={}",
+        feed_id, inserted
+    );
     Ok(inserted)
 }
 
@@ -352,24 +677,520 @@ pub fn rename_feed(conn: &Connection, id: i64, new_name: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn delete_feed(conn: &Connection, id: i64) -> Result<()> {
-    conn.execute("DELETE FROM articles WHERE feed_id = ?1", params![id])?;
-    conn.execute("DELETE FROM feeds WHERE id = ?1", params![id])?;
+// --- Feed Settings ---
+
+const FEED_SETTINGS_COLUMNS: &str =
+    "refresh_interval_minutes, retention_days, full_text, user_agent,
+     reader_mode, mark_read_on_open, notifications_muted, sort_order,
+     allow_private_network";
+
+fn settings_target(target_type: &str) -> Result<&'static str> {
+    match target_type {
+        "feed" => Ok("feed_id"),
+        "folder" => Ok("folder_id"),
+        _ => Err(FeedMeeError::InvalidInput(format!(
+            "Invalid settings target: {}",
+            target_type
+        ))),
+    }
+}
+
+/// Overrides stored for one feed or folder; all `None` if there are none.
+pub fn get_feed_settings(conn: &Connection, target_type: &str, id: i64) -> Result<FeedSettings> {
+    let column = settings_target(target_type)?;
+    let sql = format!(
+        "SELECT {} FROM feed_settings WHERE {} = ?1",
+        FEED_SETTINGS_COLUMNS, column
+    );
+    match conn.query_row(&sql, [id], |r| {
+        Ok(FeedSettings {
+            refresh_interval_minutes: r.get(0)?,
+            retention_days: r.get(1)?,
+            full_text: r.get(2)?,
+            user_agent: r.get(3)?,
+            reader_mode: r.get(4)?,
+            mark_read_on_open: r.get(5)?,
+            notifications_muted: r.get(6)?,
+            sort_order: r.get(7)?,
+            allow_private_network: r.get(8)?,
+        })
+    }) {
+        Ok(settings) => Ok(settings),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(FeedSettings::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub fn save_feed_settings(
+    conn: &Connection,
+    target_type: &str,
+    id: i64,
+    settings: &FeedSettings,
+) -> Result<()> {
+    let column = settings_target(target_type)?;
+    let sql = format!(
+        "INSERT INTO feed_settings ({column}, {cols})
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+         ON CONFLICT({column}) DO UPDATE SET
+            refresh_interval_minutes = excluded.refresh_interval_minutes,
+            retention_days = excluded.retention_days,
+            full_text = excluded.full_text,
+            user_agent = excluded.user_agent,
+            reader_mode = excluded.reader_mode,
+            mark_read_on_open = excluded.mark_read_on_open,
+            notifications_muted = excluded.notifications_muted,
+            sort_order = excluded.sort_order,
+            allow_private_network = excluded.allow_private_network",
+        column = column,
+        cols = FEED_SETTINGS_COLUMNS
+    );
+    conn.execute(
+        &sql,
+        params![
+            id,
+            settings.refresh_interval_minutes,
+            settings.retention_days,
+            settings.full_text,
+            settings.user_agent,
+            settings.reader_mode,
+            settings.mark_read_on_open,
+            settings.notifications_muted,
+            settings.sort_order,
+            settings.allow_private_network
+        ],
+    )?;
+    Ok(())
+}
+
+/// Feed overrides layered over its folder's; globals are applied by the caller.
+pub fn get_layered_feed_settings(conn: &Connection, feed_id: i64) -> Result<FeedSettings> {
+    let folder_id: i64 = conn
+        .query_row(
+            "SELECT folder_id FROM feeds WHERE id = ?1",
+            [feed_id],
+            |r| r.get(0),
+        )
+        .map_err(|e| feed_not_found(e, feed_id))?;
+    Ok(get_feed_settings(conn, "feed", feed_id)?.or(get_feed_settings(conn, "folder", folder_id)?))
+}
+
+/// Feeds whose resolved refresh interval has elapsed since their last refresh.
+/// `default_interval_minutes` applies where neither feed nor folder sets one.
+pub fn get_due_feed_ids(
+    conn: &Connection,
+    default_interval_minutes: u64,
+    now: i64,
+) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare(
+        "SELECT id FROM (
+            SELECT f.id, f.last_refreshed_at,
+                   COALESCE(fs.refresh_interval_minutes, ds.refresh_interval_minutes, ?1)
+                       AS interval_minutes
+            FROM feeds f
+            LEFT JOIN feed_settings fs ON fs.feed_id = f.id
+            LEFT JOIN feed_settings ds ON ds.folder_id = f.folder_id
+            WHERE f.deleted_at IS NULL AND f.feed_type != 'orphans'
+         )
+         WHERE interval_minutes > 0 AND last_refreshed_at + interval_minutes * 60 <= ?2
+         ORDER BY last_refreshed_at",
+    )?;
+    let ids = stmt
+        .query_map(params![default_interval_minutes as i64, now], |r| r.get(0))?
+        .collect::<rusqlite::Result<Vec<i64>>>()?;
+    Ok(ids)
+}
+
+// --- Feed Credentials ---
+
+/// Stored credentials for a feed; empty if it has none.
+pub fn get_feed_auth(conn: &Connection, feed_id: i64) -> Result<FeedAuth> {
+    match conn.query_row(
+        "SELECT username, password, bearer_token, cookies, headers
+         FROM feed_auth WHERE feed_id = ?1",
+        [feed_id],
+        |r| {
+            let headers: Option<String> = r.get(4)?;
+            Ok(FeedAuth {
+                username: r.get(0)?,
+                password: r.get(1)?,
+                bearer_token: r.get(2)?,
+                cookies: r.get(3)?,
+                headers: headers.and_then(|h| serde_json::from_str(&h).ok()),
+            })
+        },
+    ) {
+        Ok(auth) => Ok(auth),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(FeedAuth::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Replaces a feed's credentials; empty credentials delete the row.
+pub fn save_feed_auth(conn: &Connection, feed_id: i64, auth: &FeedAuth) -> Result<()> {
+    if auth.is_empty() {
+        conn.execute("DELETE FROM feed_auth WHERE feed_id = ?1", [feed_id])?;
+        return Ok(());
+    }
+    let headers = auth
+        .headers
+        .as_ref()
+        .filter(|h| !h.is_empty())
+        .map(serde_json::to_string)
+        .transpose()?;
+    conn.execute(
+        "INSERT INTO feed_auth (feed_id, username, password, bearer_token, cookies, headers)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(feed_id) DO UPDATE SET
+            username = excluded.username,
+            password = excluded.password,
+            bearer_token = excluded.bearer_token,
+            cookies = excluded.cookies,
+            headers = excluded.headers",
+        params![
+            feed_id,
+            auth.username,
+            auth.password,
+            auth.bearer_token,
+            auth.cookies,
+            headers
+        ],
+    )?;
+    Ok(())
+}
+
+// --- Scrape Configs ---
+
+/// A website feed's scraping rules, if it has any.
+pub fn get_scrape_config(conn: &Connection, feed_id: i64) -> Result<Option<ScrapeConfig>> {
+    let patterns = |json: String| serde_json::from_str(&json).unwrap_or_default();
+    match conn.query_row(
+        "SELECT item_selector, title_selector, link_selector, date_selector, summary_selector,
+                include_patterns, exclude_patterns, fetch_article_pages
+         FROM feed_scrape_config WHERE feed_id = ?1",
+        [feed_id],
+        |r| {
+            Ok(ScrapeConfig {
+                item_selector: r.get(0)?,
+                title_selector: r.get(1)?,
+                link_selector: r.get(2)?,
+                date_selector: r.get(3)?,
+                summary_selector: r.get(4)?,
+                include_patterns: patterns(r.get(5)?),
+                exclude_patterns: patterns(r.get(6)?),
+                fetch_article_pages: r.get(7)?,
+            })
+        },
+    ) {
+        Ok(config) => Ok(Some(config)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Replaces a feed's scraping rules; `None` goes back to the link heuristics.
+pub fn save_scrape_config(
+    conn: &Connection,
+    feed_id: i64,
+    config: Option<&ScrapeConfig>,
+) -> Result<()> {
+    let Some(config) = config else {
+        conn.execute(
+            "DELETE FROM feed_scrape_config WHERE feed_id = ?1",
+            [feed_id],
+        )?;
+        return Ok(());
+    };
+    conn.execute(
+        "INSERT INTO feed_scrape_config (feed_id, item_selector, title_selector, link_selector,
+                                         date_selector, summary_selector, include_patterns,
+                                         exclude_patterns, fetch_article_pages)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+         ON CONFLICT(feed_id) DO UPDATE SET
+            item_selector = excluded.item_selector,
+            title_selector = excluded.title_selector,
+            link_selector = excluded.link_selector,
+            date_selector = excluded.date_selector,
+            summary_selector = excluded.summary_selector,
+            include_patterns = excluded.include_patterns,
+            exclude_patterns = excluded.exclude_patterns,
+            fetch_article_pages = excluded.fetch_article_pages",
+        params![
+            feed_id,
+            config.item_selector,
+            config.title_selector,
+            config.link_selector,
+            config.date_selector,
+            config.summary_selector,
+            serde_json::to_string(&config.include_patterns)?,
+            serde_json::to_string(&config.exclude_patterns)?,
+            config.fetch_article_pages
+        ],
+    )?;
+    Ok(())
+}
+
+// --- Watch Feeds ---
+
+/// What a watch feed compares; the defaults if none was saved.
+pub fn get_watch_config(conn: &Connection, feed_id: i64) -> Result<WatchConfig> {
+    match conn.query_row(
+        "SELECT selector, ignore_selectors, min_changed_chars FROM feed_watch WHERE feed_id = ?1",
+        [feed_id],
+        |r| {
+            Ok(WatchConfig {
+                selector: r.get(0)?,
+                ignore_selectors: serde_json::from_str(&r.get::<_, String>(1)?).unwrap_or_default(),
+                min_changed_chars: r.get(2)?,
+            })
+        },
+    ) {
+        Ok(config) => Ok(config),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(WatchConfig::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Saves a watch feed's config. Changing what is watched drops the snapshot,
+/// so the next refresh starts a new baseline instead of reporting the switch
+/// as a change.
+pub fn save_watch_config(conn: &Connection, feed_id: i64, config: &WatchConfig) -> Result<()> {
+    let selector = config
+        .selector
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    let ignore_selectors = serde_json::to_string(&config.ignore_selectors)?;
+    let unchanged: bool = conn
+        .query_row(
+            "SELECT selector IS ?2 AND ignore_selectors = ?3 FROM feed_watch WHERE feed_id = ?1",
+            params![feed_id, selector, ignore_selectors],
+            |r| r.get(0),
+        )
+        .unwrap_or(false);
+    conn.execute(
+        "INSERT INTO feed_watch (feed_id, selector, ignore_selectors, min_changed_chars)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT(feed_id) DO UPDATE SET
+            selector = excluded.selector,
+            ignore_selectors = excluded.ignore_selectors,
+            min_changed_chars = excluded.min_changed_chars",
+        params![
+            feed_id,
+            selector,
+            ignore_selectors,
+            config.min_changed_chars.max(0)
+        ],
+    )?;
+    if !unchanged {
+        conn.execute(
+            "UPDATE feed_watch SET last_text = NULL WHERE feed_id = ?1",
+            [feed_id],
+        )?;
+        conn.execute(
+            "UPDATE feeds SET content_hash = NULL WHERE id = ?1",
+            [feed_id],
+        )?;
+    }
     Ok(())
 }
 
-pub fn delete_folder(conn: &Connection, id: i64) -> Result<()> {
-    let mut stmt = conn.prepare("SELECT id FROM feeds WHERE folder_id = ?1")?;
-    let feed_ids: Vec<i64> = stmt
-        .query_map(params![id], |row| row.get(0))?
-        .collect::<Result<Vec<i64>>>()?;
-    for feed_id in feed_ids {
-        delete_feed(conn, feed_id)?;
+/// The text a watch feed saw when it last reported a change.
+pub fn get_watch_snapshot(conn: &Connection, feed_id: i64) -> Result<Option<String>> {
+    match conn.query_row(
+        "SELECT last_text FROM feed_watch WHERE feed_id = ?1",
+        [feed_id],
+        |r| r.get(0),
+    ) {
+        Ok(text) => Ok(text),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Records a new snapshot along with its hash in `feeds.content_hash`.
+pub fn save_watch_snapshot(conn: &Connection, feed_id: i64, text: &str, hash: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO feed_watch (feed_id, last_text) VALUES (?1, ?2)
+         ON CONFLICT(feed_id) DO UPDATE SET last_text = excluded.last_text",
+        params![feed_id, text],
+    )?;
+    update_feed_content_hash(conn, feed_id, hash)
+}
+
+/// Moves `user:pass@` credentials out of feed URLs into `feed_auth`. Safe to run
+/// repeatedly; runs at startup and after OPML imports.
+pub fn migrate_url_credentials(conn: &mut Connection) -> Result<()> {
+    let feeds: Vec<(i64, String)> = {
+        let mut stmt = conn.prepare("SELECT id, url FROM feeds WHERE url LIKE '%@%'")?;
+        stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?
+    };
+
+    let tx = conn.transaction()?;
+    for (id, url) in feeds {
+        let (bare, Some(auth)) = crate::auth::split_url_credentials(&url) else {
+            continue;
+        };
+        let updated = tx.execute(
+            "UPDATE OR IGNORE feeds SET url = ?1 WHERE id = ?2",
+            params![bare, id],
+        )?;
+        if updated == 0 {
+            warn!(
+                "Feed {} has credentials in its URL but {} is already subscribed; leaving it as is",
+                id, bare
+            );
+            continue;
+        }
+        let merged = auth.merged_onto(get_feed_auth(&tx, id)?);
+        save_feed_auth(&tx, id, &merged)?;
+        info!("Moved credentials out of the URL of feed {}", id);
     }
-    conn.execute("DELETE FROM folders WHERE id = ?1", params![id])?;
+    tx.commit()?;
+    Ok(())
+}
+
+// --- Trash ---
+
+pub const ORPHANS_FEED_URL: &str = "feedmee:orphans";
+
+/// Moves a feed to the trash. Its articles stay in place (hidden from every list)
+/// until the trash is purged, so a restore is lossless.
+pub fn trash_feed(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute(
+        "UPDATE feeds SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL",
+        params![chrono::Utc::now().timestamp(), id],
+    )?;
+    Ok(())
+}
+
+/// Trashes a folder and every live feed in it with the same timestamp, so they can
+/// be restored together.
+pub fn trash_folder(conn: &mut Connection, id: i64) -> Result<()> {
+    let now = chrono::Utc::now().timestamp();
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE feeds SET deleted_at = ?1 WHERE folder_id = ?2 AND deleted_at IS NULL",
+        params![now, id],
+    )?;
+    tx.execute(
+        "UPDATE folders SET deleted_at = ?1 WHERE id = ?2",
+        params![now, id],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn get_trash(conn: &Connection) -> Result<Vec<TrashItem>> {
+    let mut stmt = conn.prepare(
+        "SELECT 'folder', fo.id, fo.name, fo.deleted_at, NULL,
+                (SELECT COUNT(*) FROM feeds f WHERE f.folder_id = fo.id AND f.deleted_at = fo.deleted_at)
+         FROM folders fo WHERE fo.deleted_at IS NOT NULL
+         UNION ALL
+         SELECT 'feed', f.id, f.name, f.deleted_at, fo.name,
+                (SELECT COUNT(*) FROM articles a WHERE a.feed_id = f.id)
+         FROM feeds f JOIN folders fo ON f.folder_id = fo.id
+         WHERE f.deleted_at IS NOT NULL
+           AND (fo.deleted_at IS NULL OR fo.deleted_at != f.deleted_at)
+         ORDER BY 4 DESC",
+    )?;
+    stmt.query_map([], |r| {
+        Ok(TrashItem {
+            item_type: r.get(0)?,
+            id: r.get(1)?,
+            name: r.get(2)?,
+            deleted_at: r.get(3)?,
+            folder_name: r.get(4)?,
+            item_count: r.get(5)?,
+        })
+    })?
+    .collect::<rusqlite::Result<Vec<TrashItem>>>()
+    .map_err(Into::into)
+}
+
+pub fn restore_feed(conn: &Connection, id: i64) -> Result<()> {
+    // A feed can't be visible inside a trashed folder, so bring the folder back too
+    conn.execute(
+        "UPDATE folders SET deleted_at = NULL
+         WHERE id = (SELECT folder_id FROM feeds WHERE id = ?1)",
+        params![id],
+    )?;
+    conn.execute(
+        "UPDATE feeds SET deleted_at = NULL WHERE id = ?1",
+        params![id],
+    )?;
     Ok(())
 }
 
+pub fn restore_folder(conn: &mut Connection, id: i64) -> Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE feeds SET deleted_at = NULL
+         WHERE folder_id = ?1 AND deleted_at = (SELECT deleted_at FROM folders WHERE id = ?1)",
+        params![id],
+    )?;
+    tx.execute(
+        "UPDATE folders SET deleted_at = NULL WHERE id = ?1",
+        params![id],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Permanently removes trashed feeds and folders deleted at or before `deleted_before`.
+/// With `keep_saved`, saved articles are first re-homed in the Orphans feed.
+pub fn purge_trash(conn: &mut Connection, deleted_before: i64, keep_saved: bool) -> Result<usize> {
+    let tx = conn.transaction()?;
+    if keep_saved {
+        let orphans_id = ensure_orphans_feed(&tx)?;
+        let moved = tx.execute(
+            "UPDATE articles SET feed_id = ?1
+             WHERE is_saved = 1 AND feed_id IN
+                (SELECT id FROM feeds WHERE deleted_at IS NOT NULL AND deleted_at <= ?2)",
+            params![orphans_id, deleted_before],
+        )?;
+        if moved > 0 {
+            info!("purge_trash: kept {} saved articles as orphans", moved);
+        }
+    }
+    tx.execute(
+        "DELETE FROM articles WHERE feed_id IN
+            (SELECT id FROM feeds WHERE deleted_at IS NOT NULL AND deleted_at <= ?1)",
+        params![deleted_before],
+    )?;
+    let feeds = tx.execute(
+        "DELETE FROM feeds WHERE deleted_at IS NOT NULL AND deleted_at <= ?1",
+        params![deleted_before],
+    )?;
+    // Anything still referencing a purged folder falls back to Uncategorized
+    tx.execute(
+        "UPDATE feeds SET folder_id = 1 WHERE folder_id IN
+            (SELECT id FROM folders WHERE deleted_at IS NOT NULL AND deleted_at <= ?1 AND id != 1)",
+        params![deleted_before],
+    )?;
+    let folders = tx.execute(
+        "DELETE FROM folders WHERE deleted_at IS NOT NULL AND deleted_at <= ?1 AND id != 1",
+        params![deleted_before],
+    )?;
+    tx.execute(
+        "UPDATE folders SET deleted_at = NULL WHERE id = 1 AND deleted_at <= ?1",
+        params![deleted_before],
+    )?;
+    tx.commit()?;
+    info!("purge_trash: removed {} feeds, {} folders", feeds, folders);
+    Ok(feeds + folders)
+}
+
+fn ensure_orphans_feed(conn: &Connection) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO feeds (name, url, folder_id, has_error, feed_type)
+         VALUES ('Orphaned Articles', ?1, 1, 0, 'orphans')
+         ON CONFLICT(url) DO UPDATE SET deleted_at = NULL",
+        params![ORPHANS_FEED_URL],
+    )?;
+    get_feed_id_by_url(conn, ORPHANS_FEED_URL)
+}
+
 pub fn move_feed(conn: &Connection, feed_id: i64, target_folder_id: i64) -> Result<()> {
     conn.execute(
         "UPDATE feeds SET folder_id = ?1 WHERE id = ?2",
@@ -377,22 +1198,3 @@ pub fn move_feed(conn: &Connection, feed_id: i64, target_folder_id: i64) -> Resu
     )?;
     Ok(())
 }
-
-pub fn search_articles(
-    conn: &Connection,
-    query: &str,
-    limit: usize,
-    offset: usize,
-    sort_asc: bool,
-) -> Result<Vec<Article>> {
-    let order = if sort_asc { "ASC" } else { "DESC" };
-    let pattern = format!("%{}%", query);
-    let sql = format!(
-        "SELECT id, feed_id, title, author, summary, url, timestamp, is_read, is_saved
-         FROM articles WHERE title LIKE ?1
-         ORDER BY timestamp {} LIMIT ?2 OFFSET ?3",
-        order
-    );
-    let mut stmt = conn.prepare(&sql)?;
-    map_articles(&mut stmt, params![pattern, limit as i64, offset as i64])
-}
@@ -0,0 +1,333 @@
+use crate::auth::{self, FeedCredentials};
+use crate::charset;
+use crate::error::Result;
+use crate::http::{self, HttpClient};
+use crate::models::{FeedCandidate, FeedSource};
+use crate::sitemap::{self, Sitemap};
+use crate::transformers;
+use crate::url_policy::UrlPolicy;
+use feed_rs::model::{Feed, FeedType};
+use log::debug;
+use scraper::{Html, Selector};
+use std::io::Cursor;
+use url::Url;
+
+const FEED_TYPES: [&str; 4] = [
+    "application/rss+xml",
+    "application/atom+xml",
+    "application/feed+json",
+    "application/rdf+xml",
+];
+
+/// Probed on the page's origin when the page doesn't advertise enough feeds itself.
+const WELL_KNOWN_PATHS: [&str; 5] = [
+    "/feed",
+    "/rss.xml",
+    "/atom.xml",
+    "/index.xml",
+    "/?feed=rss2",
+];
+
+/// Upper bound on candidate URLs fetched for one page.
+const MAX_PROBES: usize = 16;
+
+/// A URL that might be a feed, before it has been fetched.
+#[derive(Debug)]
+pub struct FeedLink {
+    pub url: String,
+    pub title: Option<String>,
+    pub source: FeedSource,
+}
+
+/// Finds every feed a page points to: `<link rel="alternate">` tags first, then
+/// `<a>` links whose URL looks like a feed. Duplicates are dropped.
+pub fn feed_links(html: &str, base: &Url) -> Vec<FeedLink> {
+    let document = Html::parse_document(html);
+    let mut links: Vec<FeedLink> = Vec::new();
+    let mut push = |href: &str, title: Option<String>, source| {
+        let Ok(url) = base.join(href.trim()) else {
+            return;
+        };
+        let url = url.to_string();
+        if !links.iter().any(|l| l.url == url) {
+            links.push(FeedLink { url, title, source });
+        }
+    };
+
+    if let Ok(sel) = Selector::parse("link[href][type]") {
+        for el in document.select(&sel) {
+            let el = el.value();
+            let kind = el.attr("type").unwrap_or_default().to_ascii_lowercase();
+            if FEED_TYPES.iter().any(|t| kind.contains(t)) {
+                let title = el.attr("title").map(|t| t.trim().to_string());
+                push(el.attr("href").unwrap_or_default(), title, FeedSource::Link);
+            }
+        }
+    }
+
+    if let Ok(sel) = Selector::parse("a[href]") {
+        for el in document.select(&sel) {
+            let href = el.value().attr("href").unwrap_or_default();
+            if base.join(href).is_ok_and(|u| looks_like_feed(&u)) {
+                let text = el.text().collect::<String>().trim().to_string();
+                let title = Some(text).filter(|t| !t.is_empty());
+                push(href, title, FeedSource::Anchor);
+            }
+        }
+    }
+
+    links
+}
+
+/// Conventional feed locations on the page's origin (WordPress, Hugo, Jekyll...).
+pub fn well_known_links(base: &Url) -> Vec<FeedLink> {
+    WELL_KNOWN_PATHS
+        .iter()
+        .filter_map(|path| base.join(path).ok())
+        .map(|url| FeedLink {
+            url: url.to_string(),
+            title: None,
+            source: FeedSource::WellKnown,
+        })
+        .collect()
+}
+
+fn looks_like_feed(url: &Url) -> bool {
+    let path = url.path().trim_end_matches('/').to_ascii_lowercase();
+    let query = url.query().unwrap_or_default().to_ascii_lowercase();
+    [
+        ".rss",
+        ".atom",
+        ".rdf",
+        "/feed",
+        "/rss",
+        "/atom",
+        "rss.xml",
+        "atom.xml",
+        "feed.xml",
+        "index.xml",
+        "feed.json",
+    ]
+    .iter()
+    .any(|suffix| path.ends_with(suffix))
+        || query.contains("feed=rss")
+        || query.contains("feed=atom")
+}
+
+/// Every feed reachable from `url`: the URL itself if it is a feed, otherwise the
+/// feeds the page links to plus well-known paths, each fetched and parsed so only
+/// working feeds are offered. Main feeds sort ahead of comment feeds. A site with
+/// no feed at all is offered its sitemaps instead.
+pub async fn discover(
+    client: &HttpClient,
+    policy: UrlPolicy,
+    credentials: Option<&FeedCredentials>,
+    url: &str,
+) -> Result<Vec<FeedCandidate>> {
+    if let Some(found) = platform_candidate(client, policy, credentials, url).await {
+        return Ok(vec![found]);
+    }
+
+    let response = auth::authorize(client.get(url, policy)?, credentials, url)
+        .send()
+        .await?
+        .error_for_status()?;
+    let page_url = response.url().clone();
+    let content_type = http::content_type(&response);
+    let body = client.read_body(response).await?;
+
+    let feed_bytes = charset::feed_to_utf8(&body, content_type.as_deref());
+    if let Ok(feed) = feed_rs::parser::parse(Cursor::new(&feed_bytes)) {
+        return Ok(vec![candidate(
+            feed,
+            page_url.to_string(),
+            None,
+            FeedSource::Direct,
+        )]);
+    }
+
+    let mut links = {
+        let html = charset::decode_html(&body, content_type.as_deref(), &page_url);
+        feed_links(&html, &page_url)
+    };
+    for link in well_known_links(&page_url) {
+        if !links.iter().any(|l| l.url == link.url) {
+            links.push(link);
+        }
+    }
+    links.truncate(MAX_PROBES);
+    debug!("discover: probing {} candidates for {}", links.len(), url);
+
+    let probes = links
+        .into_iter()
+        .map(|link| {
+            let client = client.clone();
+            let credentials = credentials.cloned();
+            tauri::async_runtime::spawn(async move {
+                probe(&client, policy, credentials.as_ref(), link).await
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut candidates: Vec<FeedCandidate> = Vec::new();
+    for probe in probes {
+        if let Ok(Some(found)) = probe.await
+            && !candidates.iter().any(|c| c.url == found.url)
+        {
+            candidates.push(found);
+        }
+    }
+    candidates.sort_by_key(|c| c.is_comments);
+    if candidates.is_empty() {
+        candidates = sitemap_candidates(client, policy, credentials, &page_url).await;
+    }
+    Ok(candidates)
+}
+
+/// Sitemaps listed in robots.txt, then those at the usual paths, that parse.
+async fn sitemap_candidates(
+    client: &HttpClient,
+    policy: UrlPolicy,
+    credentials: Option<&FeedCredentials>,
+    page_url: &Url,
+) -> Vec<FeedCandidate> {
+    let mut urls = match page_url.join("/robots.txt") {
+        Ok(robots) => fetch_text(client, policy, credentials, robots.as_str())
+            .await
+            .map(|text| sitemap::robots_sitemaps(&text))
+            .unwrap_or_default(),
+        Err(_) => Vec::new(),
+    };
+    for path in sitemap::SITEMAP_PATHS {
+        if let Ok(url) = page_url.join(path)
+            && !urls.contains(&url.to_string())
+        {
+            urls.push(url.to_string());
+        }
+    }
+    urls.truncate(MAX_PROBES);
+    debug!("discover: probing {} sitemaps for {}", urls.len(), page_url);
+
+    let probes = urls
+        .into_iter()
+        .map(|url| {
+            let client = client.clone();
+            let credentials = credentials.cloned();
+            tauri::async_runtime::spawn(async move {
+                let request =
+                    auth::authorize(client.get(&url, policy).ok()?, credentials.as_ref(), &url);
+                let found = sitemap::fetch(&client, request).await.ok()?;
+                Some(sitemap_candidate(&found, url))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut candidates = Vec::new();
+    for probe in probes {
+        if let Ok(Some(found)) = probe.await {
+            candidates.push(found);
+        }
+    }
+    candidates
+}
+
+async fn fetch_text(
+    client: &HttpClient,
+    policy: UrlPolicy,
+    credentials: Option<&FeedCredentials>,
+    url: &str,
+) -> Option<String> {
+    let request = auth::authorize(client.get(url, policy).ok()?, credentials, url);
+    let response = request.send().await.ok()?.error_for_status().ok()?;
+    let body = client.read_body(response).await.ok()?;
+    Some(String::from_utf8_lossy(&body).into_owned())
+}
+
+fn sitemap_candidate(found: &Sitemap, url: String) -> FeedCandidate {
+    let host = Url::parse(&url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default();
+    let kind = if found.is_news() {
+        "News sitemap"
+    } else {
+        "Sitemap"
+    };
+    FeedCandidate {
+        title: format!("{} ({})", kind, host),
+        format: "sitemap".to_string(),
+        entry_count: if found.is_index() {
+            found.sitemaps.len()
+        } else {
+            found.urls.len()
+        },
+        is_comments: false,
+        source: FeedSource::Sitemap,
+        url,
+    }
+}
+
+/// The feed a platform page maps to (see `transformers`), if that feed loads.
+/// A failed rewrite falls back to ordinary discovery of the page itself.
+pub async fn platform_candidate(
+    client: &HttpClient,
+    policy: UrlPolicy,
+    credentials: Option<&FeedCredentials>,
+    url: &str,
+) -> Option<FeedCandidate> {
+    let link = FeedLink {
+        url: transformers::feed_url(url)?,
+        title: None,
+        source: FeedSource::Platform,
+    };
+    probe(client, policy, credentials, link).await
+}
+
+/// Fetches a candidate; `None` unless it parses as a feed.
+async fn probe(
+    client: &HttpClient,
+    policy: UrlPolicy,
+    credentials: Option<&FeedCredentials>,
+    link: FeedLink,
+) -> Option<FeedCandidate> {
+    let request = auth::authorize(client.get(&link.url, policy).ok()?, credentials, &link.url);
+    let response = request.send().await.ok()?.error_for_status().ok()?;
+    let final_url = response.url().to_string();
+    let body = client.read_feed(response).await.ok()?;
+    match feed_rs::parser::parse(Cursor::new(body)) {
+        Ok(feed) => Some(candidate(feed, final_url, link.title, link.source)),
+        Err(e) => {
+            debug!("discover: {} is not a feed: {}", link.url, e);
+            None
+        },
+    }
+}
+
+fn candidate(
+    feed: Feed,
+    url: String,
+    link_title: Option<String>,
+    source: FeedSource,
+) -> FeedCandidate {
+    let title = feed
+        .title
+        .map(|t| t.content.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .or(link_title)
+        .unwrap_or_else(|| url.clone());
+    let format = match feed.feed_type {
+        FeedType::Atom => "atom",
+        FeedType::JSON => "json",
+        FeedType::RSS0 | FeedType::RSS1 | FeedType::RSS2 => "rss",
+    };
+    let is_comments =
+        title.to_lowercase().contains("comments") || url.to_lowercase().contains("comments");
+    FeedCandidate {
+        url,
+        title,
+        format: format.to_string(),
+        entry_count: feed.entries.len(),
+        is_comments,
+        source,
+    }
+}
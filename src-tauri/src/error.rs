@@ -0,0 +1,185 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt;
+
+pub type Result<T, E = FeedMeeError> = std::result::Result<T, E>;
+
+/// Error type shared by the database layer and every Tauri command.
+///
+/// Serialized to the frontend as `{ kind, message, feed_id, status }` so the UI
+/// can branch on `kind` instead of parsing message strings.
+#[derive(Debug)]
+pub enum FeedMeeError {
+    Database(String),
+    NotFound(String),
+    Network {
+        message: String,
+        status: Option<u16>,
+        feed_id: Option<i64>,
+    },
+    Timeout {
+        message: String,
+        feed_id: Option<i64>,
+    },
+    Parse {
+        message: String,
+        feed_id: Option<i64>,
+    },
+    Io(String),
+    InvalidInput(String),
+}
+
+impl FeedMeeError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Database(_) => "database",
+            Self::NotFound(_) => "not_found",
+            Self::Network { .. } => "network",
+            Self::Timeout { .. } => "timeout",
+            Self::Parse { .. } => "parse",
+            Self::Io(_) => "io",
+            Self::InvalidInput(_) => "invalid_input",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Database(m)
+            | Self::NotFound(m)
+            | Self::Io(m)
+            | Self::InvalidInput(m)
+            | Self::Network { message: m, .. }
+            | Self::Timeout { message: m, .. }
+            | Self::Parse { message: m, .. } => m,
+        }
+    }
+
+    pub fn feed_id(&self) -> Option<i64> {
+        match self {
+            Self::Network { feed_id, .. }
+            | Self::Timeout { feed_id, .. }
+            | Self::Parse { feed_id, .. } => *feed_id,
+            _ => None,
+        }
+    }
+
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Network { status, .. } => *status,
+            _ => None,
+        }
+    }
+
+    pub fn parse(message: impl Into<String>) -> Self {
+        Self::Parse {
+            message: message.into(),
+            feed_id: None,
+        }
+    }
+
+    /// Attach the feed that was being processed when the error occurred.
+    pub fn for_feed(mut self, id: i64) -> Self {
+        match &mut self {
+            Self::Network { feed_id, .. }
+            | Self::Timeout { feed_id, .. }
+            | Self::Parse { feed_id, .. } => *feed_id = Some(id),
+            _ => {},
+        }
+        self
+    }
+}
+
+impl fmt::Display for FeedMeeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network {
+                message,
+                status: Some(status),
+                ..
+            } => write!(f, "{} (HTTP {})", message, status),
+            _ => f.write_str(self.message()),
+        }
+    }
+}
+
+impl std::error::Error for FeedMeeError {}
+
+impl Serialize for FeedMeeError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("FeedMeeError", 4)?;
+        s.serialize_field("kind", self.kind())?;
+        s.serialize_field("message", &self.to_string())?;
+        s.serialize_field("feed_id", &self.feed_id())?;
+        s.serialize_field("status", &self.status())?;
+        s.end()
+    }
+}
+
+impl From<rusqlite::Error> for FeedMeeError {
+    fn from(e: rusqlite::Error) -> Self {
+        match e {
+            rusqlite::Error::QueryReturnedNoRows => Self::NotFound("Record not found".to_string()),
+            e => Self::Database(e.to_string()),
+        }
+    }
+}
+
+impl From<rusqlite_migration::Error> for FeedMeeError {
+    fn from(e: rusqlite_migration::Error) -> Self {
+        Self::Database(format!("Migration failed: {}", e))
+    }
+}
+
+impl From<reqwest::Error> for FeedMeeError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::Timeout {
+                message: e.to_string(),
+                feed_id: None,
+            }
+        } else if e.is_decode() {
+            Self::parse(e.to_string())
+        } else {
+            Self::Network {
+                message: e.to_string(),
+                status: e.status().map(|s| s.as_u16()),
+                feed_id: None,
+            }
+        }
+    }
+}
+
+impl From<feed_rs::parser::ParseFeedError> for FeedMeeError {
+    fn from(e: feed_rs::parser::ParseFeedError) -> Self {
+        Self::parse(format!("Parse error: {}", e))
+    }
+}
+
+impl From<opml::Error> for FeedMeeError {
+    fn from(e: opml::Error) -> Self {
+        Self::parse(format!("Invalid OPML: {}", e))
+    }
+}
+
+impl From<serde_json::Error> for FeedMeeError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::parse(e.to_string())
+    }
+}
+
+impl From<url::ParseError> for FeedMeeError {
+    fn from(e: url::ParseError) -> Self {
+        Self::InvalidInput(format!("Invalid URL: {}", e))
+    }
+}
+
+impl From<std::io::Error> for FeedMeeError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
+
+impl From<tauri::Error> for FeedMeeError {
+    fn from(e: tauri::Error) -> Self {
+        Self::Io(e.to_string())
+    }
+}
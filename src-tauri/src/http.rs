@@ -0,0 +1,191 @@
+use crate::charset;
+use crate::error::{FeedMeeError, Result};
+use crate::settings::AppSettings;
+use crate::url_policy::{PublicResolver, UrlPolicy};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Certificate, Client, Proxy, RequestBuilder, Response};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Sent when the user hasn't configured their own User-Agent.
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "FeedMee/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/dcog989/FeedMee)"
+);
+
+/// The shared HTTP clients plus the limits they enforce. Cheap to clone; rebuilt
+/// whenever the network settings change.
+///
+/// There is one client per `UrlPolicy`: the default one resolves hostnames through
+/// `PublicResolver`, the other serves feeds allowed onto the local network.
+#[derive(Clone)]
+pub struct HttpClient {
+    public: Client,
+    private: Client,
+    max_response_bytes: u64,
+}
+
+impl HttpClient {
+    pub fn from_settings(settings: &AppSettings) -> Result<Self> {
+        Ok(Self {
+            public: build_client(settings, UrlPolicy::PUBLIC)?,
+            private: build_client(settings, UrlPolicy::for_feed(true))?,
+            max_response_bytes: settings.max_response_mb.saturating_mul(1024 * 1024),
+        })
+    }
+
+    /// The largest body accepted, also applied to local feed files and commands.
+    pub fn max_response_bytes(&self) -> u64 {
+        self.max_response_bytes
+    }
+
+    /// Starts a GET request, or fails if `policy` doesn't allow the URL.
+    pub fn get(&self, url: &str, policy: UrlPolicy) -> Result<RequestBuilder> {
+        let url = policy.check(url)?;
+        let client = if policy.allow_private_network {
+            &self.private
+        } else {
+            &self.public
+        };
+        Ok(client.get(url))
+    }
+
+    /// Reads a response body, failing once it grows past the configured limit
+    /// rather than buffering an unbounded download.
+    pub async fn read_body(&self, mut response: Response) -> Result<Vec<u8>> {
+        let too_large = || {
+            FeedMeeError::parse(format!(
+                "Response is larger than the {} MB limit",
+                self.max_response_bytes / (1024 * 1024)
+            ))
+        };
+        if response
+            .content_length()
+            .is_some_and(|len| len > self.max_response_bytes)
+        {
+            return Err(too_large());
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if (body.len() + chunk.len()) as u64 > self.max_response_bytes {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    /// Reads an HTML page and decodes it using its declared or detected charset.
+    pub async fn read_html(&self, response: Response) -> Result<String> {
+        let content_type = content_type(&response);
+        let url = response.url().clone();
+        let body = self.read_body(response).await?;
+        Ok(charset::decode_html(&body, content_type.as_deref(), &url))
+    }
+
+    /// Reads a feed document, transcoded to UTF-8 when only the HTTP header
+    /// names its charset.
+    pub async fn read_feed(&self, response: Response) -> Result<Vec<u8>> {
+        let content_type = content_type(&response);
+        let body = self.read_body(response).await?;
+        Ok(charset::feed_to_utf8(&body, content_type.as_deref()).into_owned())
+    }
+}
+
+pub fn content_type(response: &Response) -> Option<String> {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+fn build_client(settings: &AppSettings, policy: UrlPolicy) -> Result<Client> {
+    let user_agent = match settings.user_agent.trim() {
+        "" => DEFAULT_USER_AGENT,
+        ua => ua,
+    };
+    let mut builder = Client::builder()
+        .user_agent(user_agent)
+        .connect_timeout(Duration::from_secs(settings.connect_timeout_seconds))
+        .timeout(Duration::from_secs(settings.request_timeout_seconds))
+        .gzip(settings.compression)
+        .brotli(settings.compression)
+        .deflate(settings.compression)
+        .redirect(policy.redirect_policy());
+
+    // Without an explicit proxy reqwest still honours HTTP(S)_PROXY from the environment.
+    // Behind a proxy the resolver would only ever see the proxy's own (often local) host.
+    let proxy = settings.proxy_url.trim();
+    if !policy.allow_private_network && proxy.is_empty() && !env_proxy_set() {
+        builder = builder.dns_resolver(Arc::new(PublicResolver));
+    }
+    if !proxy.is_empty() {
+        let proxy = Proxy::all(proxy)
+            .map_err(|e| FeedMeeError::InvalidInput(format!("Invalid proxy: {}", e)))?;
+        builder = builder.proxy(proxy);
+    }
+
+    for path in settings
+        .ca_cert_paths
+        .iter()
+        .filter(|p| !p.trim().is_empty())
+    {
+        for cert in load_certificates(path)? {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    builder
+        .build()
+        .map_err(|e| FeedMeeError::InvalidInput(format!("Invalid network settings: {}", e)))
+}
+
+fn env_proxy_set() -> bool {
+    [
+        "ALL_PROXY",
+        "HTTPS_PROXY",
+        "HTTP_PROXY",
+        "all_proxy",
+        "https_proxy",
+        "http_proxy",
+    ]
+    .iter()
+    .any(|var| std::env::var_os(var).is_some_and(|v| !v.is_empty()))
+}
+
+/// Whether two settings would produce different clients.
+pub fn config_changed(old: &AppSettings, new: &AppSettings) -> bool {
+    old.proxy_url != new.proxy_url
+        || old.connect_timeout_seconds != new.connect_timeout_seconds
+        || old.request_timeout_seconds != new.request_timeout_seconds
+        || old.user_agent != new.user_agent
+        || old.compression != new.compression
+        || old.max_response_mb != new.max_response_mb
+        || old.ca_cert_paths != new.ca_cert_paths
+}
+
+/// A PEM file may hold a whole bundle; anything else is tried as a single DER cert.
+fn load_certificates(path: &str) -> Result<Vec<Certificate>> {
+    let bytes = std::fs::read(path).map_err(|e| {
+        FeedMeeError::InvalidInput(format!("Cannot read CA certificate {}: {}", path, e))
+    })?;
+    let certs = if bytes.starts_with(b"-----BEGIN") {
+        Certificate::from_pem_bundle(&bytes)
+    } else {
+        Certificate::from_der(&bytes).map(|c| vec![c])
+    };
+    match certs {
+        Ok(certs) if !certs.is_empty() => Ok(certs),
+        Ok(_) => Err(FeedMeeError::InvalidInput(format!(
+            "No certificates found in {}",
+            path
+        ))),
+        Err(e) => Err(FeedMeeError::InvalidInput(format!(
+            "Invalid CA certificate {}: {}",
+            path, e
+        ))),
+    }
+}
@@ -1,17 +1,44 @@
+pub mod auth;
+pub mod backup;
+pub mod charset;
 pub mod commands;
 pub mod db;
+pub mod discovery;
+pub mod error;
+pub mod http;
+pub mod local;
+pub mod maintenance;
 pub mod models;
+pub mod pool;
+pub mod sanitize;
+pub mod scrape;
 pub mod settings;
+pub mod sitemap;
+pub mod structured;
+pub mod transformers;
+pub mod url_policy;
+pub mod watch;
 
 #[allow(unused_imports)]
 use log::{error, info, warn};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tauri::Manager;
 
+const SCHEDULER_TICK: Duration = Duration::from_secs(15 * 60);
+
 pub struct AppState {
-    db: Mutex<rusqlite::Connection>,
+    db: Arc<pool::DbPool>,
     settings: Mutex<settings::AppSettings>,
-    pub http_client: reqwest::Client,
+    http: Mutex<http::HttpClient>,
+    local_sources: local::TrustedSources,
+}
+
+impl AppState {
+    /// The current HTTP client; replaced when the network settings change.
+    pub fn http(&self) -> http::HttpClient {
+        self.http.lock().unwrap().clone()
+    }
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -62,9 +89,9 @@ pub fn run() {
             // ---------------------
 
             // Load Settings
-            let mut app_settings = settings::load_settings(&app_data_dir);
+            let (app_settings, settings_notes) = settings::load_settings(&app_data_dir);
 
-            let log_level = match app_settings.log_level.to_lowercase().as_str() {
+            let log_level = match app_settings.log_level.as_str() {
                 "error" => LevelFilter::Error,
                 "warn" => LevelFilter::Warn,
                 "debug" => LevelFilter::Debug,
@@ -93,46 +120,49 @@ pub fn run() {
             ]);
 
             info!("Starting FeedMee application");
-            info!("Settings loaded: {:?}", app_settings);
+            for note in &settings_notes {
+                warn!("{}", note);
+            }
+            info!("Settings loaded: {:?}", app_settings.redacted());
 
             let db_path = db_dir.join("feedmee.sqlite");
 
-            let mut conn = rusqlite::Connection::open(&db_path).map_err(|e| {
+            let pool = pool::DbPool::open(&db_path).map_err(|e| {
                 error!("Failed to open database: {}", e);
                 format!("Database open failed: {}", e)
             })?;
 
-            if let Err(e) = db::init_db(&mut conn) {
-                error!("Schema initialization failed: {}", e);
-                panic!("Schema init failed: {}", e);
-            }
-
-            // Check Vacuum (every 24 hours = 86400 seconds)
             let now = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs() as i64;
-            if now - app_settings.last_vacuum > 86400 {
-                if let Err(e) = db::run_vacuum(&conn) {
-                    error!("Maintenance VACUUM failed: {}", e);
-                } else {
-                    app_settings.last_vacuum = now;
-                    settings::save_settings(&app_data_dir, &app_settings);
-                }
+
+            // Purge trash past its retention period
+            let retention_secs = (app_settings.trash_retention_days as i64).saturating_mul(86400);
+            if let Err(e) = db::purge_trash(
+                &mut pool.writer(),
+                now - retention_secs,
+                app_settings.keep_saved_on_delete,
+            ) {
+                error!("Trash purge failed: {}", e);
             }
 
-            let http_client = reqwest::Client::builder()
-                .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-                .timeout(std::time::Duration::from_secs(10))
-                .build()
-                .expect("failed to build HTTP client");
+            // A missing CA file or similar shouldn't stop the app from starting
+            let http_client = http::HttpClient::from_settings(&app_settings).unwrap_or_else(|e| {
+                error!("Network settings unusable, using defaults: {}", e);
+                http::HttpClient::from_settings(&settings::AppSettings::default())
+                    .expect("failed to build HTTP client")
+            });
 
             app.manage(AppState {
-                db: Mutex::new(conn),
+                db: Arc::new(pool),
                 settings: Mutex::new(app_settings),
-                http_client,
+                http: Mutex::new(http_client),
+                local_sources: local::TrustedSources::load(app_data_dir.join("local_feeds.json")),
             });
 
+            spawn_scheduler(app.handle().clone());
+
             Ok(())
         })
         .plugin(tauri_plugin_opener::init())
@@ -142,10 +172,7 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::get_app_info,
             commands::get_folders_with_feeds,
-            commands::get_articles_for_feed,
-            commands::get_articles_for_folder,
-            commands::get_latest_articles,
-            commands::get_saved_articles,
+            commands::query_articles,
             commands::get_app_settings,
             commands::save_app_settings, // Added
             commands::get_shortcuts,
@@ -155,22 +182,80 @@ pub fn run() {
             commands::mark_article_read,
             commands::mark_all_read,
             commands::import_opml,
-            commands::export_opml,
-            commands::write_file,
+            commands::export_opml_to_path,
             commands::refresh_feed,
             commands::refresh_all_feeds,
+            commands::refresh_due_feeds,
+            commands::get_due_feeds,
+            commands::get_feed_settings,
+            commands::save_feed_settings,
+            commands::get_effective_feed_settings,
+            commands::get_feed_auth,
+            commands::save_feed_auth,
+            commands::get_scrape_config,
+            commands::save_scrape_config,
+            commands::preview_scrape,
+            commands::get_watch_config,
+            commands::save_watch_config,
             commands::add_feed,
+            commands::discover_feeds,
             commands::rename_folder,
             commands::rename_feed,
             commands::delete_feed,
             commands::delete_folder,
+            commands::get_trash,
+            commands::restore_from_trash,
+            commands::empty_trash,
+            commands::get_db_health,
+            commands::compact_database,
+            commands::confirm_local_feed,
+            commands::list_backups,
+            commands::create_backup,
+            commands::restore_backup,
+            commands::export_backup,
+            commands::import_backup,
             commands::move_feed,
             commands::get_article_content,
-            commands::get_feed_unread_count,
-            commands::search_articles
+            commands::get_feed_unread_count
         ])
         .run(tauri::generate_context!())
         .unwrap_or_else(|e| {
             panic!("error while running tauri application: {}", e);
         });
 }
+
+/// Runs scheduled backups and database maintenance. The first tick runs right
+/// away but on its own thread, so startup never waits on either.
+fn spawn_scheduler(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        loop {
+            let state = app.state::<AppState>();
+            let (interval_hours, keep) = {
+                let settings = state.settings.lock().unwrap();
+                (
+                    settings.backup_interval_hours,
+                    settings.backup_keep_count as usize,
+                )
+            };
+
+            // Back up before maintenance, so the snapshot predates any VACUUM.
+            if interval_hours > 0 {
+                let dir = backup::backup_dir(state.db.path());
+                let result =
+                    backup::backup_if_due(&state.db.reader(), &dir, interval_hours, keep.max(1));
+                if let Err(e) = result {
+                    error!("Scheduled backup failed: {}", e);
+                }
+            }
+
+            if let Err(e) = maintenance::run_if_due(&state.db) {
+                error!("Database maintenance failed: {}", e);
+            }
+            if let Err(e) = maintenance::sanitize_summaries_if_needed(&state.db) {
+                error!("Summary sanitizing failed: {}", e);
+            }
+
+            std::thread::sleep(SCHEDULER_TICK);
+        }
+    });
+}
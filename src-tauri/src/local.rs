@@ -0,0 +1,360 @@
+use crate::error::{FeedMeeError, Result};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+use url::Url;
+
+/// How long a feed command may run before it is killed.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Tail of a failing command's stderr kept as the feed's error.
+const MAX_STDERR_CHARS: usize = 1000;
+/// Bytes of stderr held while the command runs, enough for `MAX_STDERR_CHARS`.
+const MAX_STDERR_BYTES: usize = 4 * MAX_STDERR_CHARS;
+
+/// A feed read from this machine rather than fetched, written the way newsboat
+/// writes them: `file:///path`, `exec:command`, or `filter:command:url` to pipe a
+/// fetched feed through a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocalSource {
+    File(PathBuf),
+    Exec(String),
+    Filter { command: String, url: String },
+}
+
+impl LocalSource {
+    /// Recognises local sources, including bare absolute paths. Anything else,
+    /// web URLs included, is `None`.
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        if let Some(command) = url.strip_prefix("exec:") {
+            let command = command.trim();
+            return (!command.is_empty()).then(|| Self::Exec(command.to_string()));
+        }
+        if let Some(rest) = url.strip_prefix("filter:") {
+            // The command may contain colons of its own; the URL starts at the last `:http`
+            let split = [":http://", ":https://"]
+                .iter()
+                .filter_map(|scheme| rest.rfind(scheme))
+                .max()?;
+            let (command, url) = (rest[..split].trim(), &rest[split + 1..]);
+            return (!command.is_empty()).then(|| Self::Filter {
+                command: command.to_string(),
+                url: url.to_string(),
+            });
+        }
+        if url.starts_with("file:") {
+            return Url::parse(url).ok()?.to_file_path().ok().map(Self::File);
+        }
+        let path = Path::new(url);
+        path.is_absolute().then(|| Self::File(path.to_path_buf()))
+    }
+
+    /// The `feed_type` stored for feeds with this source.
+    pub fn feed_type(&self) -> &'static str {
+        match self {
+            Self::File(_) => "file",
+            Self::Exec(_) | Self::Filter { .. } => "exec",
+        }
+    }
+
+    /// The form saved as the feed's URL, which `parse` reads back.
+    pub fn to_url(&self) -> String {
+        match self {
+            Self::File(path) => Url::from_file_path(path)
+                .map(|u| u.to_string())
+                .unwrap_or_else(|_| path.display().to_string()),
+            Self::Exec(command) => format!("exec:{}", command),
+            Self::Filter { command, url } => format!("filter:{}:{}", command, url),
+        }
+    }
+
+    /// A feed name for when the output has no title of its own.
+    pub fn default_title(&self) -> String {
+        match self {
+            Self::File(path) => path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            Self::Exec(command) | Self::Filter { command, .. } => command.clone(),
+        }
+    }
+}
+
+/// Local sources the user has allowed on this computer, by their saved URL.
+///
+/// Kept in a file beside the settings rather than in the database, so feeds
+/// arriving in an imported or restored database, or an OPML file, can't read
+/// files or run commands until the user confirms them here.
+pub struct TrustedSources {
+    path: PathBuf,
+    urls: Mutex<BTreeSet<String>>,
+}
+
+impl TrustedSources {
+    /// Loads the list at `path`; a missing or unreadable file trusts nothing.
+    pub fn load(path: PathBuf) -> Self {
+        let urls = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                log::warn!("local: ignoring unreadable {}: {}", path.display(), e);
+                BTreeSet::new()
+            }),
+            Err(_) => BTreeSet::new(),
+        };
+        Self {
+            path,
+            urls: Mutex::new(urls),
+        }
+    }
+
+    pub fn contains(&self, url: &str) -> bool {
+        self.urls.lock().unwrap().contains(url)
+    }
+
+    pub fn insert(&self, url: &str) -> Result<()> {
+        let mut urls = self.urls.lock().unwrap();
+        urls.insert(url.to_string());
+        self.save(&urls)
+    }
+
+    /// Forgets every confirmation, for when the database is swapped for another.
+    pub fn clear(&self) -> Result<()> {
+        let mut urls = self.urls.lock().unwrap();
+        urls.clear();
+        self.save(&urls)
+    }
+
+    fn save(&self, urls: &BTreeSet<String>) -> Result<()> {
+        std::fs::write(&self.path, serde_json::to_string_pretty(urls)?)?;
+        Ok(())
+    }
+}
+
+/// Reads a feed file, refusing ones over `max_bytes`.
+pub async fn read_file(path: &Path, max_bytes: u64) -> Result<Vec<u8>> {
+    let cannot_read =
+        |e: std::io::Error| FeedMeeError::Io(format!("Cannot read {}: {}", path.display(), e));
+    let size = tokio::fs::metadata(path).await.map_err(cannot_read)?.len();
+    if size > max_bytes {
+        return Err(too_large(max_bytes));
+    }
+    tokio::fs::read(path).await.map_err(cannot_read)
+}
+
+/// Runs `command` through the shell and returns what it printed. A non-zero exit,
+/// empty output or running past `COMMAND_TIMEOUT` fails with the end of stderr
+/// as the message.
+pub async fn run(command: &str, stdin: Option<Vec<u8>>, max_bytes: u64) -> Result<Vec<u8>> {
+    let mut child = shell(command)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| FeedMeeError::Io(format!("Cannot run {:?}: {}", command, e)))?;
+
+    // Written from its own task so a command that prints before reading all of
+    // its input can't deadlock against us
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        tauri::async_runtime::spawn(async move {
+            let _ = pipe.write_all(&input).await;
+        });
+    }
+
+    let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        return Err(FeedMeeError::Io(format!(
+            "Cannot read output of {:?}",
+            command
+        )));
+    };
+
+    // stderr drains alongside so a chatty command can't stall on a full pipe.
+    // Output past the limit fails at once: returning drops the child, which
+    // kills it, as does the timeout.
+    let stderr = tauri::async_runtime::spawn(read_tail(stderr));
+    let (status, stdout, stderr) = tokio::time::timeout(COMMAND_TIMEOUT, async {
+        let stdout = read_limited(stdout, max_bytes).await?;
+        let status = child.wait().await?;
+        Ok::<_, FeedMeeError>((status, stdout, stderr.await??))
+    })
+    .await
+    .map_err(|_| FeedMeeError::Timeout {
+        message: format!(
+            "Command timed out after {}s: {}",
+            COMMAND_TIMEOUT.as_secs(),
+            command
+        ),
+        feed_id: None,
+    })??;
+
+    let stderr = stderr_tail(&stderr);
+    if !status.success() {
+        return Err(FeedMeeError::Io(match stderr {
+            Some(stderr) => format!("Command failed ({}): {}", status, stderr),
+            None => format!("Command failed ({})", status),
+        }));
+    }
+    if stdout.iter().all(u8::is_ascii_whitespace) {
+        return Err(FeedMeeError::parse(match stderr {
+            Some(stderr) => format!("Command printed no feed: {}", stderr),
+            None => "Command printed no feed".to_string(),
+        }));
+    }
+    if let Some(stderr) = stderr {
+        log::debug!("local: {:?} wrote to stderr: {}", command, stderr);
+    }
+    Ok(stdout)
+}
+
+/// Reads all of `pipe`, failing as soon as it goes past `max_bytes`.
+async fn read_limited(pipe: impl AsyncRead + Unpin, max_bytes: u64) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    pipe.take(max_bytes.saturating_add(1))
+        .read_to_end(&mut buf)
+        .await?;
+    if buf.len() as u64 > max_bytes {
+        return Err(too_large(max_bytes));
+    }
+    Ok(buf)
+}
+
+/// Reads `pipe` to the end, keeping only the last `MAX_STDERR_BYTES`.
+async fn read_tail(mut pipe: impl AsyncRead + Unpin) -> Result<Vec<u8>> {
+    let mut tail = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = pipe.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(tail);
+        }
+        tail.extend_from_slice(&chunk[..n]);
+        if tail.len() > MAX_STDERR_BYTES {
+            tail.drain(..tail.len() - MAX_STDERR_BYTES);
+        }
+    }
+}
+
+fn too_large(max_bytes: u64) -> FeedMeeError {
+    FeedMeeError::parse(format!(
+        "Feed is larger than the {} MB limit",
+        max_bytes / (1024 * 1024)
+    ))
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command).creation_flags(CREATE_NO_WINDOW);
+    cmd
+}
+
+fn stderr_tail(stderr: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(stderr);
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let skip = text.chars().count().saturating_sub(MAX_STDERR_CHARS);
+    Some(text.chars().skip(skip).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LocalSource;
+
+    fn round_trip(input: &str, expected: LocalSource, url: &str) {
+        let source = LocalSource::parse(input);
+        assert_eq!(source.as_ref(), Some(&expected), "input: {}", input);
+        assert_eq!(expected.to_url(), url, "input: {}", input);
+        assert_eq!(LocalSource::parse(url), Some(expected), "url: {}", url);
+    }
+
+    #[test]
+    fn exec() {
+        round_trip(
+            "exec: ~/bin/feed.sh --all ",
+            LocalSource::Exec("~/bin/feed.sh --all".to_string()),
+            "exec:~/bin/feed.sh --all",
+        );
+        assert_eq!(LocalSource::parse("exec:  "), None);
+    }
+
+    #[test]
+    fn filter() {
+        round_trip(
+            "filter:sed 's/a/b/':https://example.com/feed.xml",
+            LocalSource::Filter {
+                command: "sed 's/a/b/'".to_string(),
+                url: "https://example.com/feed.xml".to_string(),
+            },
+            "filter:sed 's/a/b/':https://example.com/feed.xml",
+        );
+        // The URL starts at the last `:http`, whichever scheme comes first
+        round_trip(
+            "filter:grep -v x:http://old.example.com:https://example.com/feed.xml",
+            LocalSource::Filter {
+                command: "grep -v x:http://old.example.com".to_string(),
+                url: "https://example.com/feed.xml".to_string(),
+            },
+            "filter:grep -v x:http://old.example.com:https://example.com/feed.xml",
+        );
+        assert_eq!(
+            LocalSource::parse("filter::https://example.com/feed.xml"),
+            None
+        );
+        assert_eq!(LocalSource::parse("filter:cat"), None);
+    }
+
+    #[test]
+    fn ignores_web_urls() {
+        assert_eq!(LocalSource::parse("https://example.com/feed.xml"), None);
+        assert_eq!(LocalSource::parse("example.com/feed.xml"), None);
+        assert_eq!(LocalSource::parse("feeds/local.xml"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_paths() {
+        round_trip(
+            "/home/me/feeds/news.xml",
+            LocalSource::File("/home/me/feeds/news.xml".into()),
+            "file:///home/me/feeds/news.xml",
+        );
+        round_trip(
+            "file:///home/me/my%20feeds/news.xml",
+            LocalSource::File("/home/me/my feeds/news.xml".into()),
+            "file:///home/me/my%20feeds/news.xml",
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_paths() {
+        round_trip(
+            r"C:\Users\me\feeds\news.xml",
+            LocalSource::File(r"C:\Users\me\feeds\news.xml".into()),
+            "file:///C:/Users/me/feeds/news.xml",
+        );
+        round_trip(
+            "file:///C:/Users/me/my%20feeds/news.xml",
+            LocalSource::File(r"C:\Users\me\my feeds\news.xml".into()),
+            "file:///C:/Users/me/my%20feeds/news.xml",
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use crate::db;
+use crate::error::Result;
+use crate::models::DbHealth;
+use crate::pool::DbPool;
+use crate::sanitize;
+use log::{error, info, warn};
+use rusqlite::{Connection, params};
+
+const MAINTENANCE_INTERVAL_SECS: i64 = 86400;
+const FULL_CHECK_INTERVAL_SECS: i64 = 7 * 86400;
+const ANALYZE_INTERVAL_SECS: i64 = 7 * 86400;
+/// Upper bound on pages released per run, so no single pass holds the writer for long.
+const VACUUM_PAGES_PER_RUN: i64 = 2000;
+/// Summaries cleaned per write, so the backfill never holds the writer for long.
+const SANITIZE_BATCH: i64 = 500;
+
+const KEY_LAST_RUN: &str = "maintenance.last_run";
+const KEY_LAST_CHECK: &str = "maintenance.last_check";
+const KEY_LAST_FULL_CHECK: &str = "maintenance.last_full_check";
+const KEY_LAST_ANALYZE: &str = "maintenance.last_analyze";
+const KEY_INTEGRITY: &str = "maintenance.integrity";
+const KEY_VACUUMED_PAGES: &str = "maintenance.vacuumed_pages";
+const KEY_SANITIZED_UP_TO: &str = "maintenance.sanitized_up_to";
+const KEY_SANITIZE_DONE: &str = "maintenance.sanitize_done";
+
+/// Runs a maintenance pass if the last one is more than a day old.
+pub fn run_if_due(pool: &DbPool) -> Result<Option<DbHealth>> {
+    let now = chrono::Utc::now().timestamp();
+    let last_run = meta_i64(&pool.reader(), KEY_LAST_RUN)?;
+    if now - last_run < MAINTENANCE_INTERVAL_SECS {
+        return Ok(None);
+    }
+    run(pool).map(Some)
+}
+
+/// One maintenance pass: integrity check on a reader, then ANALYZE/optimize and a
+/// bounded incremental vacuum on the writer.
+///
+/// A full `integrity_check` runs weekly; other passes use the much cheaper `quick_check`.
+pub fn run(pool: &DbPool) -> Result<DbHealth> {
+    let now = chrono::Utc::now().timestamp();
+    let full = now - meta_i64(&pool.reader(), KEY_LAST_FULL_CHECK)? >= FULL_CHECK_INTERVAL_SECS;
+
+    let check = if full {
+        "integrity_check"
+    } else {
+        "quick_check"
+    };
+
+    let problems = integrity_check(&pool.reader(), check)?;
+    let integrity = if problems.is_empty() {
+        info!("Database {} passed", check);
+        "ok".to_string()
+    } else {
+        for p in &problems {
+            error!("Database integrity problem: {}", p);
+        }
+        problems.join("\n")
+    };
+
+    let conn = pool.writer();
+    db::set_meta(&conn, KEY_INTEGRITY, &integrity)?;
+    db::set_meta(&conn, KEY_LAST_CHECK, &now.to_string())?;
+    if full {
+        db::set_meta(&conn, KEY_LAST_FULL_CHECK, &now.to_string())?;
+    }
+
+    // Don't rewrite pages of a database that is already damaged.
+    if problems.is_empty() {
+        if now - meta_i64(&conn, KEY_LAST_ANALYZE)? >= ANALYZE_INTERVAL_SECS {
+            info!("Running ANALYZE");
+            conn.execute_batch("ANALYZE;")?;
+            db::set_meta(&conn, KEY_LAST_ANALYZE, &now.to_string())?;
+        }
+        conn.execute_batch("PRAGMA optimize;")?;
+
+        // Converting an older database needs a full VACUUM, which only `compact` runs
+        let mode: i64 = conn.query_row("PRAGMA auto_vacuum", [], |r| r.get(0))?;
+        let vacuumed = if mode == 2 {
+            incremental_vacuum(&conn)?
+        } else {
+            0
+        };
+        db::set_meta(&conn, KEY_VACUUMED_PAGES, &vacuumed.to_string())?;
+    } else {
+        warn!("Skipping optimize and vacuum until integrity problems are resolved");
+    }
+
+    db::set_meta(&conn, KEY_LAST_RUN, &now.to_string())?;
+    get_health(&conn)
+}
+
+/// Sanitizes summaries stored before feed HTML was cleaned on ingest. Works
+/// through the table in id order, recording progress so an interrupted run
+/// resumes. A restored backup from before the backfill lacks the done marker,
+/// so it gets cleaned too.
+pub fn sanitize_summaries_if_needed(pool: &DbPool) -> Result<()> {
+    if db::get_meta(&pool.reader(), KEY_SANITIZE_DONE)?.is_some() {
+        return Ok(());
+    }
+
+    let mut after = meta_i64(&pool.reader(), KEY_SANITIZED_UP_TO)?;
+    let mut cleaned = 0;
+    loop {
+        let rows: Vec<(i64, Option<String>, String)> = {
+            let conn = pool.reader();
+            let mut stmt = conn.prepare(
+                "SELECT id, summary, url FROM articles WHERE id > ?1 ORDER BY id LIMIT ?2",
+            )?;
+            stmt.query_map(params![after, SANITIZE_BATCH], |r| {
+                Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+            })?
+            .collect::<rusqlite::Result<_>>()?
+        };
+        let Some(&(last, _, _)) = rows.last() else {
+            break;
+        };
+
+        let mut conn = pool.writer();
+        let tx = conn.transaction()?;
+        {
+            let mut update = tx.prepare("UPDATE articles SET summary = ?1 WHERE id = ?2")?;
+            for (id, summary, url) in &rows {
+                let Some(summary) = summary else { continue };
+                let clean = sanitize::sanitize_html(summary, url);
+                if clean != *summary {
+                    update.execute(params![clean, id])?;
+                    cleaned += 1;
+                }
+            }
+        }
+        db::set_meta(&tx, KEY_SANITIZED_UP_TO, &last.to_string())?;
+        tx.commit()?;
+        after = last;
+    }
+
+    info!("Sanitized {} stored article summaries", cleaned);
+    db::set_meta(&pool.writer(), KEY_SANITIZE_DONE, "1")
+}
+
+/// Current database statistics plus the results of the last maintenance pass.
+pub fn get_health(conn: &Connection) -> Result<DbHealth> {
+    let pragma = |name: &str| -> Result<i64> {
+        Ok(conn.query_row(&format!("PRAGMA {}", name), [], |r| r.get(0))?)
+    };
+
+    let page_size = pragma("page_size")?;
+    let page_count = pragma("page_count")?;
+    let auto_vacuum = match pragma("auto_vacuum")? {
+        1 => "full",
+        2 => "incremental",
+        _ => "none",
+    };
+
+    let integrity = db::get_meta(conn, KEY_INTEGRITY)?;
+    Ok(DbHealth {
+        integrity_ok: integrity.as_deref().map(|r| r == "ok"),
+        integrity_problems: integrity
+            .filter(|r| r != "ok")
+            .map(|r| r.lines().map(str::to_string).collect())
+            .unwrap_or_default(),
+        last_check_at: meta_i64(conn, KEY_LAST_CHECK)?,
+        last_full_check_at: meta_i64(conn, KEY_LAST_FULL_CHECK)?,
+        last_analyze_at: meta_i64(conn, KEY_LAST_ANALYZE)?,
+        last_maintenance_at: meta_i64(conn, KEY_LAST_RUN)?,
+        last_vacuumed_pages: meta_i64(conn, KEY_VACUUMED_PAGES)?,
+        auto_vacuum: auto_vacuum.to_string(),
+        page_size,
+        page_count,
+        freelist_count: pragma("freelist_count")?,
+        size_bytes: page_size * page_count,
+    })
+}
+
+/// Returns the problems reported by `integrity_check` or `quick_check`; empty means healthy.
+fn integrity_check(conn: &Connection, check: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {}(100)", check))?;
+    let rows = stmt
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    Ok(rows.into_iter().filter(|r| r != "ok").collect())
+}
+
+/// Rewrites the whole database with a full VACUUM, switching databases created
+/// before incremental vacuum was enabled over to it. Blocks every write until
+/// done, so it only runs when the user asks for it.
+pub fn compact(conn: &Connection) -> Result<DbHealth> {
+    let mode: i64 = conn.query_row("PRAGMA auto_vacuum", [], |r| r.get(0))?;
+    if mode != 2 {
+        info!("Converting database to incremental auto_vacuum");
+        conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL;")?;
+    }
+    let before: i64 = conn.query_row("PRAGMA page_count", [], |r| r.get(0))?;
+    conn.execute_batch("VACUUM;")?;
+    let after: i64 = conn.query_row("PRAGMA page_count", [], |r| r.get(0))?;
+    info!("VACUUM released {} pages", before - after);
+    db::set_meta(conn, KEY_VACUUMED_PAGES, &(before - after).to_string())?;
+    get_health(conn)
+}
+
+fn incremental_vacuum(conn: &Connection) -> Result<i64> {
+    let before: i64 = conn.query_row("PRAGMA freelist_count", [], |r| r.get(0))?;
+    if before == 0 {
+        return Ok(0);
+    }
+    conn.execute_batch(&format!(
+        "PRAGMA incremental_vacuum({});",
+        VACUUM_PAGES_PER_RUN
+    ))?;
+    let after: i64 = conn.query_row("PRAGMA freelist_count", [], |r| r.get(0))?;
+    info!("Incremental vacuum released {} pages", before - after);
+    Ok(before - after)
+}
+
+fn meta_i64(conn: &Connection, key: &str) -> Result<i64> {
+    Ok(db::get_meta(conn, key)?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0))
+}
@@ -1,4 +1,6 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 
 #[derive(Serialize, Clone, Debug)]
 pub struct Article {
@@ -11,6 +13,104 @@ pub struct Article {
     pub timestamp: i64,
     pub is_read: bool,
     pub is_saved: bool,
+    pub fetched_at: i64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ArticlePage {
+    pub articles: Vec<Article>,
+    pub next_cursor: Option<String>,
+    /// The order actually used, after feed and folder overrides.
+    pub sort_asc: bool,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArticleSort {
+    #[default]
+    Date,
+    Added,
+    Title,
+    Feed,
+}
+
+impl ArticleSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Date => "date",
+            Self::Added => "added",
+            Self::Title => "title",
+            Self::Feed => "feed",
+        }
+    }
+
+    pub fn column(&self) -> &'static str {
+        match self {
+            Self::Date => "a.timestamp",
+            Self::Added => "a.id",
+            Self::Title => "a.title COLLATE NOCASE",
+            Self::Feed => "f.name COLLATE NOCASE",
+        }
+    }
+}
+
+/// Which article date `since`/`until` filter on.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DateField {
+    #[default]
+    Published,
+    Fetched,
+}
+
+impl DateField {
+    pub fn column(&self) -> &'static str {
+        match self {
+            Self::Published => "a.timestamp",
+            Self::Fetched => "a.fetched_at",
+        }
+    }
+}
+
+/// Filters, sort and page position for `db::query_articles`. Every field is
+/// optional on the wire; unset filters match everything.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ArticleQuery {
+    pub feed_id: Option<i64>,
+    pub folder_id: Option<i64>,
+    pub search: Option<String>,
+    pub author: Option<String>,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub date_field: DateField,
+    pub unread_only: bool,
+    pub saved_only: bool,
+    pub sort: ArticleSort,
+    /// Unset resolves through the feed, folder and global sort order.
+    pub sort_asc: Option<bool>,
+    pub cursor: Option<String>,
+    pub limit: usize,
+}
+
+impl Default for ArticleQuery {
+    fn default() -> Self {
+        Self {
+            feed_id: None,
+            folder_id: None,
+            search: None,
+            author: None,
+            since: None,
+            until: None,
+            date_field: DateField::Published,
+            unread_only: false,
+            saved_only: false,
+            sort: ArticleSort::Date,
+            sort_asc: None,
+            cursor: None,
+            limit: 50,
+        }
+    }
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -23,6 +123,39 @@ pub struct Feed {
     pub has_error: bool,
     pub feed_type: String,
     pub content_hash: Option<String>,
+    /// Why the last refresh failed, while `has_error` is set.
+    pub last_error: Option<String>,
+}
+
+/// Where discovery found a feed URL.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedSource {
+    /// The URL entered was itself a feed.
+    Direct,
+    /// A platform page (YouTube, GitHub...) rewritten to its native feed.
+    Platform,
+    /// `<link rel="alternate">` in the page head.
+    Link,
+    /// An `<a>` in the page body.
+    Anchor,
+    /// A conventional path such as `/feed` on the same site.
+    WellKnown,
+    /// A sitemap, offered when the site has no feed at all.
+    Sitemap,
+}
+
+/// A working feed found by `discover_feeds`, offered to the user before subscribing.
+#[derive(Serialize, Clone, Debug)]
+pub struct FeedCandidate {
+    pub url: String,
+    pub title: String,
+    /// "rss", "atom", "json" or "sitemap".
+    pub format: String,
+    /// Entries in the feed; child sitemaps for a sitemap index.
+    pub entry_count: usize,
+    pub is_comments: bool,
+    pub source: FeedSource,
 }
 
 #[derive(Serialize, Clone, Debug)]
@@ -31,3 +164,211 @@ pub struct Folder {
     pub name: String,
     pub feeds: Vec<Feed>,
 }
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TrashItem {
+    pub item_type: String,
+    pub id: i64,
+    pub name: String,
+    pub deleted_at: i64,
+    pub folder_name: Option<String>,
+    /// Articles for a feed, feeds for a folder.
+    pub item_count: i64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BackupInfo {
+    pub name: String,
+    pub size: u64,
+    pub created_at: i64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DbHealth {
+    /// `None` until the first maintenance pass has run.
+    pub integrity_ok: Option<bool>,
+    pub integrity_problems: Vec<String>,
+    pub last_check_at: i64,
+    pub last_full_check_at: i64,
+    pub last_analyze_at: i64,
+    pub last_maintenance_at: i64,
+    pub last_vacuumed_pages: i64,
+    pub auto_vacuum: String,
+    pub page_size: i64,
+    pub page_count: i64,
+    pub freelist_count: i64,
+    pub size_bytes: i64,
+}
+
+/// Per-feed or per-folder overrides. `None` inherits from the next level up
+/// (feed, then folder, then `AppSettings`).
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct FeedSettings {
+    /// Minutes between automatic refreshes; 0 never refreshes automatically.
+    pub refresh_interval_minutes: Option<u32>,
+    /// Days, counted from first seen, to keep unsaved articles that have dropped out
+    /// of the feed; 0 keeps them forever.
+    pub retention_days: Option<u32>,
+    /// Fetch and extract the full article text for new entries at refresh time.
+    pub full_text: Option<bool>,
+    pub user_agent: Option<String>,
+    /// Open articles in the extracted reader view by default.
+    pub reader_mode: Option<bool>,
+    pub mark_read_on_open: Option<bool>,
+    pub notifications_muted: Option<bool>,
+    /// "asc" or "desc".
+    pub sort_order: Option<String>,
+    /// Allow requests to localhost and private network addresses; see `UrlPolicy`.
+    pub allow_private_network: Option<bool>,
+}
+
+impl FeedSettings {
+    /// Fills every unset field from `parent`.
+    pub fn or(self, parent: FeedSettings) -> Self {
+        Self {
+            refresh_interval_minutes: self
+                .refresh_interval_minutes
+                .or(parent.refresh_interval_minutes),
+            retention_days: self.retention_days.or(parent.retention_days),
+            full_text: self.full_text.or(parent.full_text),
+            user_agent: self.user_agent.or(parent.user_agent),
+            reader_mode: self.reader_mode.or(parent.reader_mode),
+            mark_read_on_open: self.mark_read_on_open.or(parent.mark_read_on_open),
+            notifications_muted: self.notifications_muted.or(parent.notifications_muted),
+            sort_order: self.sort_order.or(parent.sort_order),
+            allow_private_network: self.allow_private_network.or(parent.allow_private_network),
+        }
+    }
+}
+
+/// Effective settings for one feed after resolving overrides against the globals.
+#[derive(Serialize, Clone, Debug)]
+pub struct ResolvedFeedSettings {
+    pub refresh_interval_minutes: u64,
+    pub retention_days: u64,
+    pub full_text: bool,
+    /// `None` uses the HTTP client's default.
+    pub user_agent: Option<String>,
+    pub reader_mode: bool,
+    pub mark_read_on_open: bool,
+    pub notifications_muted: bool,
+    pub sort_order: String,
+    pub allow_private_network: bool,
+}
+
+/// HTTP credentials for a private feed. Never serialized back to the frontend
+/// (see `FeedAuthInfo`) and redacted from `Debug`, so secrets stay out of logs.
+///
+/// When saving, `None` keeps the stored value and an empty string clears it;
+/// `headers`, when given, replaces the whole set.
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct FeedAuth {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub bearer_token: Option<String>,
+    pub cookies: Option<String>,
+    pub headers: Option<BTreeMap<String, String>>,
+}
+
+impl FeedAuth {
+    pub fn is_empty(&self) -> bool {
+        let blank = |v: &Option<String>| v.as_deref().is_none_or(str::is_empty);
+        blank(&self.username)
+            && blank(&self.password)
+            && blank(&self.bearer_token)
+            && blank(&self.cookies)
+            && self.headers.as_ref().is_none_or(BTreeMap::is_empty)
+    }
+
+    /// Applies an update from the frontend on top of the stored credentials.
+    pub fn merged_onto(self, stored: FeedAuth) -> FeedAuth {
+        let pick = |new: Option<String>, old: Option<String>| match new {
+            Some(v) if v.is_empty() => None,
+            Some(v) => Some(v),
+            None => old,
+        };
+        FeedAuth {
+            username: pick(self.username, stored.username),
+            password: pick(self.password, stored.password),
+            bearer_token: pick(self.bearer_token, stored.bearer_token),
+            cookies: pick(self.cookies, stored.cookies),
+            headers: self.headers.or(stored.headers),
+        }
+    }
+
+    pub fn info(&self) -> FeedAuthInfo {
+        let set = |v: &Option<String>| v.as_deref().is_some_and(|v| !v.is_empty());
+        FeedAuthInfo {
+            username: self.username.clone(),
+            has_password: set(&self.password),
+            has_bearer_token: set(&self.bearer_token),
+            has_cookies: set(&self.cookies),
+            header_names: self
+                .headers
+                .as_ref()
+                .map(|h| h.keys().cloned().collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl fmt::Debug for FeedAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.info(), f)
+    }
+}
+
+/// What the frontend may see of a feed's credentials: which ones are set, not their values.
+#[derive(Serialize, Clone, Debug)]
+pub struct FeedAuthInfo {
+    pub username: Option<String>,
+    pub has_password: bool,
+    pub has_bearer_token: bool,
+    pub has_cookies: bool,
+    pub header_names: Vec<String>,
+}
+
+/// How to pick articles out of a `website` feed's page. Selectors other than
+/// `item_selector` are matched inside each item; unset ones fall back to the
+/// item's own text and first link.
+///
+/// URL patterns match anywhere in the article URL, with `*` as a wildcard.
+/// An article must match one include pattern (if any) and no exclude pattern.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ScrapeConfig {
+    pub item_selector: String,
+    pub title_selector: Option<String>,
+    pub link_selector: Option<String>,
+    pub date_selector: Option<String>,
+    pub summary_selector: Option<String>,
+    pub include_patterns: Vec<String>,
+    pub exclude_patterns: Vec<String>,
+    /// Also read structured data from each new article's own page.
+    pub fetch_article_pages: bool,
+}
+
+/// What a `watch` feed compares between refreshes. Without a selector the whole
+/// page body is watched; elements matching an ignore selector (timestamps,
+/// visitor counters, ads) are left out of the comparison.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct WatchConfig {
+    pub selector: Option<String>,
+    pub ignore_selectors: Vec<String>,
+    /// Changes touching fewer characters than this are held back until they
+    /// add up, so a ticking date doesn't produce an article every refresh.
+    pub min_changed_chars: i64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            selector: None,
+            ignore_selectors: Vec::new(),
+            min_changed_chars: 10,
+        }
+    }
+}
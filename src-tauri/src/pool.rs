@@ -0,0 +1,122 @@
+use crate::db;
+use crate::error::{FeedMeeError, Result};
+use log::{debug, info};
+use rusqlite::{Connection, OpenFlags};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+const READER_COUNT: usize = 4;
+
+/// SQLite connection pool: one writer plus a fixed set of read-only connections.
+///
+/// In WAL mode readers never block the writer (or each other), so article lists
+/// stay responsive while a refresh is inserting. All access goes through
+/// `read`/`write`, which run the closure on the blocking thread pool.
+pub struct DbPool {
+    path: PathBuf,
+    writer: Mutex<Connection>,
+    readers: Mutex<Vec<Connection>>,
+    available: Condvar,
+}
+
+pub struct ReadGuard<'a> {
+    pool: &'a DbPool,
+    conn: Option<Connection>,
+}
+
+impl Deref for ReadGuard<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+            .as_ref()
+            .expect("reader connection already returned")
+    }
+}
+
+impl Drop for ReadGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.pool.readers.lock().unwrap().push(conn);
+            self.pool.available.notify_one();
+        }
+    }
+}
+
+impl DbPool {
+    /// Opens the writer, brings the schema up to date, then opens the readers.
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut writer = Connection::open(path)?;
+        db::init_db(&mut writer)?;
+
+        let readers = (0..READER_COUNT)
+            .map(|_| open_reader(path))
+            .collect::<Result<Vec<_>>>()?;
+        info!("Database pool opened with {} readers", readers.len());
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: Mutex::new(writer),
+            readers: Mutex::new(readers),
+            available: Condvar::new(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Blocking access to the writer connection. Only call this off the async runtime.
+    pub fn writer(&self) -> MutexGuard<'_, Connection> {
+        self.writer.lock().unwrap()
+    }
+
+    /// Blocking checkout of a reader connection, waiting if all are in use.
+    pub fn reader(&self) -> ReadGuard<'_> {
+        let mut readers = self.readers.lock().unwrap();
+        loop {
+            if let Some(conn) = readers.pop() {
+                return ReadGuard {
+                    pool: self,
+                    conn: Some(conn),
+                };
+            }
+            debug!("DbPool: all readers busy, waiting");
+            readers = self.available.wait(readers).unwrap();
+        }
+    }
+
+    pub async fn read<T, F>(self: &Arc<Self>, f: F) -> Result<T>
+    where
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let pool = Arc::clone(self);
+        tauri::async_runtime::spawn_blocking(move || f(&pool.reader()))
+            .await
+            .map_err(FeedMeeError::from)?
+    }
+
+    pub async fn write<T, F>(self: &Arc<Self>, f: F) -> Result<T>
+    where
+        F: FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let pool = Arc::clone(self);
+        tauri::async_runtime::spawn_blocking(move || f(&mut pool.writer()))
+            .await
+            .map_err(FeedMeeError::from)?
+    }
+}
+
+fn open_reader(path: &Path) -> Result<Connection> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY
+            | OpenFlags::SQLITE_OPEN_URI
+            | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    Ok(conn)
+}
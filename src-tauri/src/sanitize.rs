@@ -0,0 +1,145 @@
+use ammonia::{Builder, UrlRelative};
+use scraper::{Html, Selector};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use url::Url;
+
+/// Hosts that only serve tracking beacons; any image from them is dropped.
+const TRACKER_HOSTS: [&str; 8] = [
+    "feeds.feedburner.com",
+    "feeds.feedblitz.com",
+    "pixel.wp.com",
+    "stats.wordpress.com",
+    "www.google-analytics.com",
+    "pixel.quantserve.com",
+    "sb.scorecardresearch.com",
+    "ct.pinterest.com",
+];
+
+/// Cleans feed or extracted HTML before it is stored or shown.
+///
+/// Uses ammonia's allow-list (no scripts, iframes, forms, styles or event
+/// handlers), resolves relative links and images against `base_url`, drops
+/// tracking pixels and marks every link `rel="noopener noreferrer"`.
+pub fn sanitize_html(html: &str, base_url: &str) -> String {
+    if html.is_empty() {
+        return String::new();
+    }
+
+    let pixels = tracking_pixels(html);
+    let mut builder = Builder::default();
+    builder
+        .link_rel(Some("noopener noreferrer"))
+        .url_relative(match Url::parse(base_url) {
+            Ok(base) => UrlRelative::RewriteWithBase(base),
+            Err(_) => UrlRelative::Deny,
+        })
+        .attribute_filter(move |element, attribute, value| {
+            if element == "img" && attribute == "src" && is_tracker(value, &pixels) {
+                None
+            } else {
+                Some(Cow::Borrowed(value))
+            }
+        });
+    builder.clean(html).to_string()
+}
+
+/// Escapes text for use in generated HTML, in element content or a quoted attribute.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// `src` of every image declared as 1x1 or smaller.
+fn tracking_pixels(html: &str) -> HashSet<String> {
+    let Ok(selector) = Selector::parse("img[src]") else {
+        return HashSet::new();
+    };
+    let tiny = |v: Option<&str>| {
+        v.and_then(|v| v.trim().trim_end_matches("px").parse::<u32>().ok())
+            .is_some_and(|n| n <= 1)
+    };
+    Html::parse_fragment(html)
+        .select(&selector)
+        .filter(|img| tiny(img.value().attr("width")) && tiny(img.value().attr("height")))
+        .filter_map(|img| img.value().attr("src").map(str::to_string))
+        .collect()
+}
+
+fn is_tracker(src: &str, pixels: &HashSet<String>) -> bool {
+    pixels.contains(src)
+        || Url::parse(src)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| TRACKER_HOSTS.contains(&h)))
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape, sanitize_html};
+
+    const BASE: &str = "https://example.com/blog/post";
+
+    #[test]
+    fn javascript_urls_are_dropped() {
+        let html = sanitize_html(r#"<a href="javascript:alert(1)">x</a>"#, BASE);
+        assert!(!html.contains("javascript"), "{html}");
+        let html = sanitize_html(r#"<img src="JavaScript:alert(1)">"#, BASE);
+        assert!(!html.to_lowercase().contains("javascript"), "{html}");
+    }
+
+    #[test]
+    fn scripts_and_handlers_are_removed() {
+        let html = sanitize_html(r#"<p onclick="x()">hi<script>x()</script></p>"#, BASE);
+        assert_eq!(html, "<p>hi</p>");
+    }
+
+    #[test]
+    fn relative_urls_resolve_against_base() {
+        let html = sanitize_html(r#"<a href="../about">a</a><img src="/img/a.png">"#, BASE);
+        assert!(
+            html.contains(r#"href="https://example.com/about""#),
+            "{html}"
+        );
+        assert!(
+            html.contains(r#"src="https://example.com/img/a.png""#),
+            "{html}"
+        );
+    }
+
+    #[test]
+    fn relative_urls_denied_without_base() {
+        let html = sanitize_html(r#"<a href="/about">a</a>"#, "not a url");
+        assert!(!html.contains("href"), "{html}");
+    }
+
+    #[test]
+    fn links_get_rel() {
+        let html = sanitize_html(r#"<a href="https://other.org/" rel="opener">a</a>"#, BASE);
+        assert!(html.contains(r#"rel="noopener noreferrer""#), "{html}");
+        assert!(!html.contains(r#"rel="opener""#), "{html}");
+    }
+
+    #[test]
+    fn tracking_pixels_are_dropped() {
+        let html = sanitize_html(
+            r#"<img src="https://cdn.example.com/p.gif" width="1" height="1"><img src="https://pixel.wp.com/g.gif"><img src="https://cdn.example.com/photo.jpg">"#,
+            BASE,
+        );
+        assert!(
+            !html.contains("p.gif") && !html.contains("pixel.wp.com"),
+            "{html}"
+        );
+        assert!(html.contains("photo.jpg"), "{html}");
+    }
+
+    #[test]
+    fn escape_covers_markup_and_quotes() {
+        assert_eq!(
+            escape(r#"<a href="x">&</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use crate::error::{FeedMeeError, Result};
+use crate::models::{Article, ScrapeConfig};
+use crate::structured;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use log::debug;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashSet;
+use url::Url;
+
+/// Date formats tried on `<time>`-less date text, after RFC 3339 and RFC 2822.
+const DATE_FORMATS: [&str; 5] = ["%Y-%m-%d", "%B %d, %Y", "%d %B %Y", "%d/%m/%Y", "%Y/%m/%d"];
+
+/// Articles listed on a `website` feed's page. With a config, its selectors pick
+/// the items; otherwise links are guessed (see `scrape_articles_from_page`).
+/// Either way the config's URL patterns filter the result, and structured data
+/// on the page fills in dates, authors and descriptions the markup lacks.
+pub fn articles(html: &str, page_url: &str, config: Option<&ScrapeConfig>) -> Result<Vec<Article>> {
+    let selectors = config.map(Selectors::parse).transpose()?;
+    let mut articles = match &selectors {
+        Some(selectors @ Selectors { item: Some(_), .. }) => {
+            select_articles(html, page_url, selectors)
+        },
+        _ => scrape_articles_from_page(html, page_url),
+    };
+    if let Some(config) = config {
+        articles.retain(|a| url_allowed(config, &a.url));
+    }
+    if let Ok(base) = Url::parse(page_url) {
+        let metadata = structured::listing_metadata(html, &base);
+        for article in &mut articles {
+            if let Some(meta) = metadata.get(&article.url) {
+                meta.apply(article);
+            }
+        }
+    }
+    Ok(articles)
+}
+
+/// Rejects selectors that don't parse, so a bad config fails when saved rather
+/// than on every refresh.
+pub fn validate(config: &ScrapeConfig) -> Result<()> {
+    Selectors::parse(config).map(|_| ())
+}
+
+struct Selectors {
+    /// Unset for configs that only filter the guessed links by URL pattern.
+    item: Option<Selector>,
+    title: Option<Selector>,
+    link: Option<Selector>,
+    date: Option<Selector>,
+    summary: Option<Selector>,
+    any_link: Selector,
+    any_time: Selector,
+}
+
+impl Selectors {
+    fn parse(config: &ScrapeConfig) -> Result<Self> {
+        let optional = |css: &Option<String>| parse_optional_selector(css.as_deref());
+        Ok(Self {
+            item: parse_optional_selector(Some(&config.item_selector))?,
+            title: optional(&config.title_selector)?,
+            link: optional(&config.link_selector)?,
+            date: optional(&config.date_selector)?,
+            summary: optional(&config.summary_selector)?,
+            any_link: parse_selector("a[href]")?,
+            any_time: parse_selector("time[datetime]")?,
+        })
+    }
+}
+
+/// Parses a user-supplied CSS selector, reporting it as invalid input.
+pub fn parse_selector(css: &str) -> Result<Selector> {
+    Selector::parse(css)
+        .map_err(|e| FeedMeeError::InvalidInput(format!("Invalid selector {:?}: {}", css, e)))
+}
+
+/// Like `parse_selector`, but a missing or blank selector is `None`.
+pub fn parse_optional_selector(css: Option<&str>) -> Result<Option<Selector>> {
+    match css.map(str::trim) {
+        Some(css) if !css.is_empty() => parse_selector(css).map(Some),
+        _ => Ok(None),
+    }
+}
+
+fn select_articles(html: &str, page_url: &str, selectors: &Selectors) -> Vec<Article> {
+    let (Ok(base), Some(item_selector)) = (Url::parse(page_url), &selectors.item) else {
+        return vec![];
+    };
+    let document = Html::parse_document(html);
+    let mut seen = HashSet::new();
+    let mut articles = Vec::new();
+
+    for item in document.select(item_selector) {
+        // The item itself may be the link, as in `a.post-title`
+        let link = match &selectors.link {
+            Some(sel) => item.select(sel).next(),
+            None if item.value().attr("href").is_some() => Some(item),
+            None => item.select(&selectors.any_link).next(),
+        };
+        let Some(url) = link
+            .and_then(|el| el.value().attr("href"))
+            .and_then(|href| base.join(href.trim()).ok())
+            .map(|u| u.to_string())
+        else {
+            debug!("scrape: item without a link on {}", page_url);
+            continue;
+        };
+        if !seen.insert(url.clone()) {
+            continue;
+        }
+
+        let title = match &selectors.title {
+            Some(sel) => item.select(sel).next(),
+            None => link,
+        }
+        .map(text)
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| url.clone());
+        let summary = selectors
+            .summary
+            .as_ref()
+            .and_then(|sel| item.select(sel).next())
+            .map(|el| el.inner_html())
+            .unwrap_or_default();
+        let date_sel = selectors.date.as_ref().unwrap_or(&selectors.any_time);
+        let timestamp = item
+            .select(date_sel)
+            .next()
+            .and_then(|el| {
+                el.value()
+                    .attr("datetime")
+                    .and_then(parse_date)
+                    .or_else(|| parse_date(&text(el)))
+            })
+            .unwrap_or(0);
+
+        articles.push(Article {
+            id: 0,
+            feed_id: 0, // unused: ingest_articles takes the feed id separately
+            title,
+            author: String::new(),
+            summary,
+            url,
+            timestamp, // 0 when undated; ingest uses first-seen time
+            is_read: false,
+            is_saved: false,
+            fetched_at: 0,
+        });
+    }
+
+    articles
+}
+
+/// The `<time datetime>` of the `<article>` or `<li>` a link sits in.
+fn entry_date(link: ElementRef, time_sel: &Selector) -> Option<i64> {
+    let entry = link
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .take(5)
+        .find(|el| matches!(el.value().name(), "article" | "li"))?;
+    entry
+        .select(time_sel)
+        .next()
+        .and_then(|el| el.value().attr("datetime"))
+        .and_then(parse_date)
+}
+
+/// Whitespace-collapsed text content.
+fn text(el: ElementRef) -> String {
+    el.text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn parse_date(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(date) =
+        DateTime::parse_from_rfc3339(value).or_else(|_| DateTime::parse_from_rfc2822(value))
+    {
+        return Some(date.timestamp());
+    }
+    // W3C datetime as used by sitemaps, which may omit the seconds
+    if let Ok(date) = DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M%:z") {
+        return Some(date.timestamp());
+    }
+    if let Ok(date) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
+        return Some(date.and_utc().timestamp());
+    }
+    DATE_FORMATS.iter().find_map(|format| {
+        NaiveDate::parse_from_str(value, format)
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|d| d.and_utc().timestamp())
+    })
+}
+
+/// Title-cased last meaningful path segment, e.g. `/2024/my-first-post` gives
+/// "My First Post". `None` when the slug is too short to mean anything.
+pub fn title_from_slug(url: &Url) -> Option<String> {
+    let slug = url
+        .path_segments()
+        .and_then(|mut segs| segs.rfind(|s| !s.is_empty() && s.len() > 3))
+        .unwrap_or("");
+    let slug = slug.rsplit_once('.').map_or(slug, |(stem, _)| stem);
+    let from_slug = slug.replace(['-', '_'], " ");
+    if from_slug.len() < 10 {
+        return None;
+    }
+    Some(
+        from_slug
+            .split_whitespace()
+            .map(|w| {
+                let mut c = w.chars();
+                match c.next() {
+                    None => String::new(),
+                    Some(f) => f.to_uppercase().collect::<String>() + c.as_str(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+/// Whether the config's include and exclude patterns let `url` through.
+pub fn url_allowed(config: &ScrapeConfig, url: &str) -> bool {
+    fn patterns(list: &[String]) -> impl Iterator<Item = &str> {
+        list.iter().map(|p| p.trim()).filter(|p| !p.is_empty())
+    }
+    let mut include = patterns(&config.include_patterns).peekable();
+    (include.peek().is_none() || include.any(|p| matches_pattern(url, p)))
+        && !patterns(&config.exclude_patterns).any(|p| matches_pattern(url, p))
+}
+
+/// Unanchored match where `*` stands for any run of characters.
+fn matches_pattern(url: &str, pattern: &str) -> bool {
+    let mut rest = url;
+    for part in pattern.split('*').filter(|p| !p.is_empty()) {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Scrape a listing page for article links. Returns Article structs with empty
+/// summary (content is fetched on-demand via get_article_content). Links are
+/// filtered to same-domain, non-trivial hrefs, and deduplicated.
+fn scrape_articles_from_page(html: &str, page_url: &str) -> Vec<Article> {
+    debug!("scrape_articles_from_page: url={}", page_url);
+    let base = match Url::parse(page_url) {
+        Ok(u) => u,
+        Err(_) => return vec![],
+    };
+    let base_host = base.host_str().unwrap_or("").to_string();
+
+    let document = Html::parse_document(html);
+    let anchor_sel = match Selector::parse("a[href]") {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+
+    let time_sel = Selector::parse("time[datetime]").ok();
+
+    let mut seen = std::collections::HashSet::new();
+    let mut articles = Vec::new();
+
+    for el in document.select(&anchor_sel) {
+        let href = match el.value().attr("href") {
+            Some(h) => h,
+            None => continue,
+        };
+
+        let abs = match base.join(href) {
+            Ok(u) => u,
+            Err(_) => continue,
+        };
+
+        // Same domain only; skip fragment-only or javascript links
+        if abs.host_str().unwrap_or("") != base_host {
+            continue;
+        }
+        if abs.path() == base.path() {
+            continue;
+        }
+
+        let url_str = abs.to_string();
+        if !seen.insert(url_str.clone()) {
+            continue;
+        }
+
+        // Extract link text as title; fall back to title attr, then URL slug
+        let anchor_text: String = el.text().collect::<Vec<_>>().join(" ");
+        let anchor_text = anchor_text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let title = if anchor_text.len() >= 10 {
+            anchor_text.clone()
+        } else if let Some(t) = el.value().attr("title").filter(|t| t.len() >= 10) {
+            t.to_string()
+        } else if let Some(t) = title_from_slug(&abs) {
+            t
+        } else {
+            debug!("scrape: skipping, no usable title for {}", url_str);
+            continue;
+        };
+
+        // Skip navigation/category URLs - only keep URLs that look like articles
+        // (have a path depth of at least 2 segments, or passed the anchor text check)
+        let path_depth = abs
+            .path_segments()
+            .map(|s| s.filter(|p| !p.is_empty()).count())
+            .unwrap_or(0);
+        if path_depth < 2 && anchor_text.len() < 10 {
+            debug!("scrape: skipping shallow nav url {}", url_str);
+            continue;
+        }
+
+        debug!("scrape: accepting {:?} -> {}", title, url_str);
+        articles.push(Article {
+            id: 0,
+            feed_id: 0, // unused: ingest_articles takes the feed id separately
+            title,
+            author: String::new(),
+            summary: String::new(),
+            url: url_str,
+            // Undated entries (0) get their first-seen time at ingest
+            timestamp: time_sel
+                .as_ref()
+                .and_then(|sel| entry_date(el, sel))
+                .unwrap_or(0),
+            is_read: false,
+            is_saved: false,
+            fetched_at: 0,
+        });
+    }
+
+    articles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{articles, validate};
+    use crate::models::ScrapeConfig;
+
+    const PAGE: &str = r#"<html><body>
+        <a href="/posts/first-post-title">First post title</a>
+        <a href="/tags/some-longer-tag-name">Some longer tag name</a>
+        <a href="/posts/second-post-title">Second post title</a>
+    </body></html>"#;
+
+    #[test]
+    fn patterns_without_item_selector() {
+        let config = ScrapeConfig {
+            include_patterns: vec!["/posts/".to_string()],
+            ..Default::default()
+        };
+        validate(&config).unwrap();
+        let urls: Vec<String> = articles(PAGE, "https://example.com/", Some(&config))
+            .unwrap()
+            .into_iter()
+            .map(|a| a.url)
+            .collect();
+        assert!(!urls.is_empty());
+        assert!(urls.iter().all(|u| u.contains("/posts/")), "{:?}", urls);
+    }
+
+    #[test]
+    fn invalid_item_selector() {
+        let config = ScrapeConfig {
+            item_selector: "li[".to_string(),
+            ..Default::default()
+        };
+        assert!(validate(&config).is_err());
+    }
+}
@@ -1,17 +1,36 @@
+use crate::error::{FeedMeeError, Result};
+use crate::models::{FeedSettings, ResolvedFeedSettings};
 use serde::{Deserialize, Serialize};
 use std::fs;
+use std::io::Write;
 use std::path::Path;
 
+/// Bump when a stored field is renamed, removed or changes meaning, and add the
+/// matching step to `migrate`.
+pub const SETTINGS_VERSION: u32 = 1;
+
+pub const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+const VIEW_TYPES: [&str; 4] = ["latest", "saved", "folder", "feed"];
+pub const SORT_ORDERS: [&str; 2] = ["desc", "asc"];
+const PROXY_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+const MIN_AUTO_UPDATE_MINUTES: u64 = 5;
+/// Upper bounds that keep day/hour/MB values clear of overflow once converted to seconds or bytes.
+const MAX_TRASH_RETENTION_DAYS: u64 = 36500;
+const MAX_BACKUP_INTERVAL_HOURS: u64 = 8760;
+const MAX_RESPONSE_MB: u64 = 4096;
+
+const SETTINGS_FILE: &str = "settings.toml";
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct AppSettings {
+    pub version: u32,
     pub feed_refresh_debounce_minutes: u64,
     #[serde(default)]
     pub refresh_all_debounce_minutes: u64,
     pub auto_update_interval_minutes: u64,
     pub log_level: String,
     #[serde(default)]
-    pub last_vacuum: i64,
-    #[serde(default)]
     pub default_view_type: String,
     #[serde(default)]
     pub default_view_id: i64,
@@ -19,43 +38,369 @@ pub struct AppSettings {
     pub auto_collapse_folders: bool,
     #[serde(default)]
     pub mark_feed_read_on_exit: bool,
+    #[serde(default)]
+    pub latest_by_fetch_time: bool,
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u64,
+    #[serde(default = "default_true")]
+    pub keep_saved_on_delete: bool,
+    /// Hours between automatic database backups; 0 disables them.
+    #[serde(default = "default_backup_interval_hours")]
+    pub backup_interval_hours: u64,
+    #[serde(default = "default_backup_keep_count")]
+    pub backup_keep_count: u64,
+    /// Global defaults for the per-feed settings; see `FeedSettings`.
+    #[serde(default)]
+    pub article_retention_days: u64,
+    #[serde(default)]
+    pub full_text: bool,
+    #[serde(default)]
+    pub reader_mode: bool,
+    #[serde(default = "default_true")]
+    pub mark_read_on_open: bool,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+    /// `http://`, `https://`, `socks5://` or `socks5h://`; empty uses the
+    /// environment's proxy, if any.
+    pub proxy_url: String,
+    pub connect_timeout_seconds: u64,
+    pub request_timeout_seconds: u64,
+    /// Empty sends `http::DEFAULT_USER_AGENT`.
+    pub user_agent: String,
+    /// Accept gzip, brotli and deflate responses.
+    pub compression: bool,
+    pub max_response_mb: u64,
+    /// Extra trusted roots (PEM bundles or DER files), e.g. for a corporate proxy.
+    pub ca_cert_paths: Vec<String>,
+}
+
+fn default_trash_retention_days() -> u64 {
+    30
+}
+
+fn default_backup_interval_hours() -> u64 {
+    24
+}
+
+fn default_backup_keep_count() -> u64 {
+    7
+}
+
+fn default_sort_order() -> String {
+    "desc".to_string()
+}
+
+fn default_true() -> bool {
+    true
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
+            version: SETTINGS_VERSION,
             feed_refresh_debounce_minutes: 4,
             refresh_all_debounce_minutes: 0,
             auto_update_interval_minutes: 30,
             log_level: "info".to_string(),
-            last_vacuum: 0,
             default_view_type: "latest".to_string(),
             default_view_id: -1,
             auto_collapse_folders: true,
             mark_feed_read_on_exit: false,
+            latest_by_fetch_time: false,
+            trash_retention_days: default_trash_retention_days(),
+            keep_saved_on_delete: true,
+            backup_interval_hours: default_backup_interval_hours(),
+            backup_keep_count: default_backup_keep_count(),
+            article_retention_days: 0,
+            full_text: false,
+            reader_mode: false,
+            mark_read_on_open: true,
+            sort_order: default_sort_order(),
+            proxy_url: String::new(),
+            connect_timeout_seconds: 10,
+            request_timeout_seconds: 30,
+            user_agent: String::new(),
+            compression: true,
+            max_response_mb: 20,
+            ca_cert_paths: Vec::new(),
         }
     }
 }
 
-pub fn load_settings(app_dir: &Path) -> AppSettings {
-    let settings_path = app_dir.join("settings.toml");
+impl AppSettings {
+    /// Describes every field holding a value the app can't use; empty when valid.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.feed_refresh_debounce_minutes == 0 {
+            problems.push("Feed refresh debounce must be greater than 0 minutes".to_string());
+        }
+        if !valid_auto_update(self.auto_update_interval_minutes) {
+            problems.push(format!(
+                "Auto update interval must be 0 (off) or at least {} minutes",
+                MIN_AUTO_UPDATE_MINUTES
+            ));
+        }
+        if !LOG_LEVELS.contains(&self.log_level.as_str()) {
+            problems.push(format!(
+                "Log level must be one of: {}",
+                LOG_LEVELS.join(", ")
+            ));
+        }
+        if !VIEW_TYPES.contains(&self.default_view_type.as_str()) {
+            problems.push(format!(
+                "Default view must be one of: {}",
+                VIEW_TYPES.join(", ")
+            ));
+        }
+        if self.backup_keep_count == 0 {
+            problems.push("At least one backup must be kept".to_string());
+        }
+        if !SORT_ORDERS.contains(&self.sort_order.as_str()) {
+            problems.push("Sort order must be asc or desc".to_string());
+        }
+        if !valid_proxy(&self.proxy_url) {
+            problems.push(format!(
+                "Proxy must be a URL starting with one of: {}",
+                PROXY_SCHEMES.map(|s| format!("{}://", s)).join(", ")
+            ));
+        }
+        if self.connect_timeout_seconds == 0 || self.request_timeout_seconds == 0 {
+            problems.push("Network timeouts must be greater than 0 seconds".to_string());
+        }
+        if self.trash_retention_days > MAX_TRASH_RETENTION_DAYS {
+            problems.push(format!(
+                "Deleted feeds can be kept for at most {} days",
+                MAX_TRASH_RETENTION_DAYS
+            ));
+        }
+        if self.backup_interval_hours > MAX_BACKUP_INTERVAL_HOURS {
+            problems.push(format!(
+                "Backup interval must be at most {} hours",
+                MAX_BACKUP_INTERVAL_HOURS
+            ));
+        }
+        if self.max_response_mb == 0 || self.max_response_mb > MAX_RESPONSE_MB {
+            problems.push(format!(
+                "Maximum response size must be between 1 and {} MB",
+                MAX_RESPONSE_MB
+            ));
+        }
+        if self.user_agent.contains(['\r', '\n']) {
+            problems.push("User agent must be a single line".to_string());
+        }
+        problems
+    }
 
-    if settings_path.exists() {
-        let content = fs::read_to_string(&settings_path).unwrap_or_default();
-        if let Ok(settings) = toml::from_str(&content) {
-            return settings;
+    /// Resets only the invalid fields to their defaults, keeping everything else.
+    fn repair(&mut self) {
+        let defaults = Self::default();
+        if self.feed_refresh_debounce_minutes == 0 {
+            self.feed_refresh_debounce_minutes = defaults.feed_refresh_debounce_minutes;
+        }
+        if !valid_auto_update(self.auto_update_interval_minutes) {
+            self.auto_update_interval_minutes = defaults.auto_update_interval_minutes;
+        }
+        if !LOG_LEVELS.contains(&self.log_level.as_str()) {
+            self.log_level = defaults.log_level;
+        }
+        if !VIEW_TYPES.contains(&self.default_view_type.as_str()) {
+            self.default_view_type = defaults.default_view_type;
+            self.default_view_id = defaults.default_view_id;
+        }
+        if self.backup_keep_count == 0 {
+            self.backup_keep_count = defaults.backup_keep_count;
+        }
+        if !SORT_ORDERS.contains(&self.sort_order.as_str()) {
+            self.sort_order = defaults.sort_order;
+        }
+        if !valid_proxy(&self.proxy_url) {
+            self.proxy_url = defaults.proxy_url;
+        }
+        if self.connect_timeout_seconds == 0 {
+            self.connect_timeout_seconds = defaults.connect_timeout_seconds;
+        }
+        if self.request_timeout_seconds == 0 {
+            self.request_timeout_seconds = defaults.request_timeout_seconds;
+        }
+        self.trash_retention_days = self.trash_retention_days.min(MAX_TRASH_RETENTION_DAYS);
+        self.backup_interval_hours = self.backup_interval_hours.min(MAX_BACKUP_INTERVAL_HOURS);
+        if self.max_response_mb == 0 {
+            self.max_response_mb = defaults.max_response_mb;
+        }
+        self.max_response_mb = self.max_response_mb.min(MAX_RESPONSE_MB);
+        if self.user_agent.contains(['\r', '\n']) {
+            self.user_agent = defaults.user_agent;
+        }
+    }
+
+    /// Resolves layered feed/folder overrides against the global defaults.
+    pub fn resolve_feed(&self, overrides: FeedSettings) -> ResolvedFeedSettings {
+        ResolvedFeedSettings {
+            refresh_interval_minutes: overrides
+                .refresh_interval_minutes
+                .map_or(self.auto_update_interval_minutes, u64::from),
+            retention_days: overrides
+                .retention_days
+                .map_or(self.article_retention_days, u64::from),
+            full_text: overrides.full_text.unwrap_or(self.full_text),
+            user_agent: overrides.user_agent.filter(|ua| !ua.trim().is_empty()),
+            reader_mode: overrides.reader_mode.unwrap_or(self.reader_mode),
+            mark_read_on_open: overrides
+                .mark_read_on_open
+                .unwrap_or(self.mark_read_on_open),
+            notifications_muted: overrides.notifications_muted.unwrap_or(false),
+            sort_order: overrides
+                .sort_order
+                .filter(|o| SORT_ORDERS.contains(&o.as_str()))
+                .unwrap_or_else(|| self.sort_order.clone()),
+            allow_private_network: overrides.allow_private_network.unwrap_or(false),
         }
     }
 
-    let settings = AppSettings::default();
-    save_settings(app_dir, &settings);
+    /// A copy that is safe to log: proxy credentials are masked.
+    pub fn redacted(&self) -> Self {
+        Self {
+            proxy_url: redact_userinfo(&self.proxy_url),
+            ..self.clone()
+        }
+    }
+}
 
-    settings
+fn valid_auto_update(minutes: u64) -> bool {
+    minutes == 0 || minutes >= MIN_AUTO_UPDATE_MINUTES
 }
 
-pub fn save_settings(app_dir: &Path, settings: &AppSettings) {
-    let settings_path = app_dir.join("settings.toml");
-    if let Ok(toml_string) = toml::to_string_pretty(settings) {
-        let _ = fs::write(settings_path, toml_string);
+fn valid_proxy(proxy: &str) -> bool {
+    let proxy = proxy.trim();
+    proxy.is_empty()
+        || url::Url::parse(proxy).is_ok_and(|u| {
+            PROXY_SCHEMES.contains(&u.scheme()) && u.host_str().is_some_and(|h| !h.is_empty())
+        })
+}
+
+fn redact_userinfo(proxy: &str) -> String {
+    match url::Url::parse(proxy.trim()) {
+        Ok(mut u) if !u.username().is_empty() || u.password().is_some() => {
+            let _ = u.set_username("***");
+            let _ = u.set_password(None);
+            u.to_string()
+        },
+        _ => proxy.to_string(),
     }
 }
+
+/// Upgrades a stored settings table in place to `SETTINGS_VERSION`.
+/// Files written before versioning have no `version` key and count as 0.
+fn migrate(table: &mut toml::Table) -> u32 {
+    let from = table
+        .get("version")
+        .and_then(|v| v.as_integer())
+        .unwrap_or(0) as u32;
+    let mut version = from;
+
+    if version < 1 {
+        // v1: VACUUM bookkeeping moved into the database maintenance task, and
+        // log_level is validated, so normalize the case the old loader tolerated.
+        table.remove("last_vacuum");
+        if let Some(level) = table.get("log_level").and_then(|v| v.as_str()) {
+            let level = level.to_lowercase();
+            table.insert("log_level".to_string(), toml::Value::String(level));
+        }
+        version = 1;
+    }
+
+    table.insert("version".to_string(), toml::Value::Integer(version as i64));
+    from
+}
+
+/// Loads settings, never failing: a missing file gives defaults, an unreadable one
+/// is set aside as `settings.toml.bad-<timestamp>` before falling back, and invalid
+/// fields are reset individually.
+///
+/// This runs before logging is set up, so notable events are returned for the
+/// caller to log.
+pub fn load_settings(app_dir: &Path) -> (AppSettings, Vec<String>) {
+    let settings_path = app_dir.join(SETTINGS_FILE);
+    let mut notes = Vec::new();
+
+    if !settings_path.exists() {
+        let settings = AppSettings::default();
+        if let Err(e) = save_settings(app_dir, &settings) {
+            notes.push(format!("Failed to write default settings: {}", e));
+        }
+        return (settings, notes);
+    }
+
+    let parsed = fs::read_to_string(&settings_path)
+        .map_err(FeedMeeError::from)
+        .and_then(|content| content.parse::<toml::Table>().map_err(parse_error))
+        .and_then(|mut table| {
+            let from = migrate(&mut table);
+            if from < SETTINGS_VERSION {
+                notes.push(format!(
+                    "Migrated settings from version {} to {}",
+                    from, SETTINGS_VERSION
+                ));
+            }
+            AppSettings::deserialize(table).map_err(parse_error)
+        });
+
+    let mut settings = match parsed {
+        Ok(settings) => settings,
+        Err(e) => {
+            let backup = app_dir.join(format!(
+                "{}.bad-{}",
+                SETTINGS_FILE,
+                chrono::Local::now().format("%Y%m%d-%H%M%S")
+            ));
+            notes.push(format!(
+                "Settings could not be read ({}); moved to {:?} and using defaults",
+                e, backup
+            ));
+            if let Err(e) = fs::rename(&settings_path, &backup) {
+                notes.push(format!("Failed to set aside unreadable settings: {}", e));
+            }
+            AppSettings::default()
+        },
+    };
+
+    let problems = settings.validate();
+    if !problems.is_empty() {
+        notes.push(format!(
+            "Reset invalid settings to defaults: {}",
+            problems.join("; ")
+        ));
+        settings.repair();
+    }
+    settings.version = SETTINGS_VERSION;
+
+    if !notes.is_empty()
+        && let Err(e) = save_settings(app_dir, &settings)
+    {
+        notes.push(format!("Failed to save settings: {}", e));
+    }
+
+    (settings, notes)
+}
+
+/// Writes settings atomically: a temp file is written and synced, then renamed
+/// over `settings.toml`, so a crash mid-write never leaves a truncated file.
+pub fn save_settings(app_dir: &Path, settings: &AppSettings) -> Result<()> {
+    let settings_path = app_dir.join(SETTINGS_FILE);
+    let tmp_path = app_dir.join(format!("{}.tmp", SETTINGS_FILE));
+
+    let toml_string = toml::to_string_pretty(settings)
+        .map_err(|e| FeedMeeError::Io(format!("Failed to serialize settings: {}", e)))?;
+
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(toml_string.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&tmp_path, &settings_path)?;
+    Ok(())
+}
+
+fn parse_error(e: toml::de::Error) -> FeedMeeError {
+    FeedMeeError::parse(format!("Invalid settings file: {}", e))
+}
@@ -0,0 +1,334 @@
+use crate::error::{FeedMeeError, Result};
+use crate::http::HttpClient;
+use crate::models::{Article, ScrapeConfig};
+use crate::sanitize::escape;
+use crate::scrape::{self, parse_date};
+use quick_xml::Reader;
+use quick_xml::events::Event;
+use reqwest::RequestBuilder;
+use std::collections::HashSet;
+use url::Url;
+
+/// Where sites usually publish their sitemaps, besides what robots.txt lists.
+pub const SITEMAP_PATHS: [&str; 4] = [
+    "/sitemap.xml",
+    "/sitemap_index.xml",
+    "/news-sitemap.xml",
+    "/sitemap-news.xml",
+];
+
+/// Newest entries kept from one refresh; full sitemaps can list every page a site has.
+const MAX_ENTRIES: usize = 100;
+
+/// Child sitemaps read from a sitemap index per refresh, most recently modified first.
+pub const MAX_CHILD_SITEMAPS: usize = 3;
+
+/// One `<url>` of a urlset, or one `<sitemap>` of an index.
+#[derive(Debug, Default, Clone)]
+pub struct SitemapEntry {
+    pub loc: String,
+    pub lastmod: Option<i64>,
+    /// `<news:publication_date>`, preferred over `lastmod` as the article date.
+    pub published: Option<i64>,
+    /// `<news:title>`.
+    pub title: Option<String>,
+    /// The first `<image:loc>`.
+    pub image: Option<String>,
+}
+
+/// A parsed `urlset` or `sitemapindex`; only one of the lists is filled.
+#[derive(Debug, Default)]
+pub struct Sitemap {
+    pub urls: Vec<SitemapEntry>,
+    pub sitemaps: Vec<SitemapEntry>,
+}
+
+impl Sitemap {
+    pub fn is_index(&self) -> bool {
+        !self.sitemaps.is_empty()
+    }
+
+    /// Whether this is a Google News sitemap rather than a plain page list.
+    pub fn is_news(&self) -> bool {
+        self.urls
+            .iter()
+            .any(|u| u.title.is_some() || u.published.is_some())
+    }
+
+    /// Child sitemaps, most recently modified first.
+    pub fn newest_sitemaps(&self, limit: usize) -> Vec<String> {
+        let mut sitemaps = self.sitemaps.clone();
+        sitemaps.sort_by_key(|s| std::cmp::Reverse(s.lastmod));
+        sitemaps.into_iter().take(limit).map(|s| s.loc).collect()
+    }
+}
+
+/// Parses a sitemap or sitemap index, failing on any other XML document.
+pub fn parse(xml: &[u8]) -> Result<Sitemap> {
+    let invalid =
+        |e: &dyn std::fmt::Display| FeedMeeError::parse(format!("Invalid sitemap: {}", e));
+    let mut reader = Reader::from_reader(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut buf = Vec::new();
+    let mut sitemap = Sitemap::default();
+    let mut root_seen = false;
+    let mut entry: Option<SitemapEntry> = None;
+    let mut in_image = false;
+    // <video:title> shares the local name of <news:title>
+    let mut in_video = false;
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf).map_err(|e| invalid(&e))? {
+            Event::Start(e) => {
+                let name = e.local_name();
+                if !root_seen {
+                    if !matches!(name.as_ref(), b"urlset" | b"sitemapindex") {
+                        return Err(FeedMeeError::parse("Not a sitemap"));
+                    }
+                    root_seen = true;
+                } else if matches!(name.as_ref(), b"url" | b"sitemap") {
+                    entry = Some(SitemapEntry::default());
+                } else if name.as_ref() == b"image" {
+                    in_image = true;
+                } else if name.as_ref() == b"video" {
+                    in_video = true;
+                }
+                text.clear();
+            },
+            Event::Empty(e) if !root_seen => {
+                // An empty <urlset/> is still a sitemap
+                if !matches!(e.local_name().as_ref(), b"urlset" | b"sitemapindex") {
+                    return Err(FeedMeeError::parse("Not a sitemap"));
+                }
+                root_seen = true;
+            },
+            Event::Text(t) => text.push_str(&t.decode().map_err(|e| invalid(&e))?),
+            Event::CData(c) => text.push_str(&c.decode().map_err(|e| invalid(&e))?),
+            // Entities such as &amp; in URLs arrive as separate events
+            Event::GeneralRef(r) => {
+                if r.is_char_ref() {
+                    if let Some(c) = r.resolve_char_ref().map_err(|e| invalid(&e))? {
+                        text.push(c);
+                    }
+                } else {
+                    let name = r.decode().map_err(|e| invalid(&e))?;
+                    let entity = format!("&{};", name);
+                    text.push_str(&quick_xml::escape::unescape(&entity).unwrap_or_default());
+                }
+            },
+            Event::End(e) => {
+                let value = text.trim();
+                match (e.local_name().as_ref(), entry.as_mut()) {
+                    (b"url", _) => {
+                        sitemap
+                            .urls
+                            .extend(entry.take().filter(|e| !e.loc.is_empty()));
+                    },
+                    (b"sitemap", _) => {
+                        sitemap
+                            .sitemaps
+                            .extend(entry.take().filter(|e| !e.loc.is_empty()));
+                    },
+                    (b"image", _) => in_image = false,
+                    (b"video", _) => in_video = false,
+                    (b"loc", Some(entry)) if in_image => {
+                        entry.image.get_or_insert_with(|| value.to_string());
+                    },
+                    (b"loc", Some(entry)) => entry.loc = value.to_string(),
+                    (b"lastmod", Some(entry)) => entry.lastmod = parse_date(value),
+                    (b"publication_date", Some(entry)) => entry.published = parse_date(value),
+                    (b"title", Some(_)) if in_image || in_video => {},
+                    (b"title", Some(entry)) if !value.is_empty() => {
+                        entry.title = Some(value.to_string());
+                    },
+                    _ => {},
+                }
+                text.clear();
+            },
+            Event::Eof => break,
+            _ => {},
+        }
+        buf.clear();
+    }
+
+    if !root_seen {
+        return Err(FeedMeeError::parse("Not a sitemap"));
+    }
+    Ok(sitemap)
+}
+
+/// Fetches and parses one sitemap or sitemap index.
+pub async fn fetch(client: &HttpClient, request: RequestBuilder) -> Result<Sitemap> {
+    let response = request.send().await?.error_for_status()?;
+    let body = client.read_body(response).await?;
+    parse(&body)
+}
+
+/// `Sitemap:` lines from a robots.txt.
+pub fn robots_sitemaps(robots: &str) -> Vec<String> {
+    robots
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case("sitemap")
+                .then(|| value.trim().to_string())
+        })
+        .filter(|url| !url.is_empty())
+        .collect()
+}
+
+/// Turns sitemap entries into articles: filtered by the feed's URL patterns,
+/// newest first and capped at `MAX_ENTRIES`.
+pub fn articles(entries: Vec<SitemapEntry>, config: Option<&ScrapeConfig>) -> Vec<Article> {
+    let mut entries = entries
+        .into_iter()
+        .filter(|e| config.is_none_or(|c| scrape::url_allowed(c, &e.loc)))
+        .collect::<Vec<_>>();
+    let mut seen = HashSet::new();
+    entries.retain(|e| seen.insert(e.loc.clone()));
+    entries.sort_by_key(|e| std::cmp::Reverse(e.published.or(e.lastmod)));
+
+    entries
+        .into_iter()
+        .take(MAX_ENTRIES)
+        .map(|entry| {
+            let title = entry
+                .title
+                .or_else(|| {
+                    Url::parse(&entry.loc)
+                        .ok()
+                        .and_then(|u| scrape::title_from_slug(&u))
+                })
+                .unwrap_or_else(|| entry.loc.clone());
+            let summary = entry
+                .image
+                .map(|src| format!("<p><img src=\"{}\"></p>", escape(&src)))
+                .unwrap_or_default();
+            Article {
+                id: 0,
+                feed_id: 0, // unused: ingest_articles takes the feed id separately
+                title,
+                author: String::new(),
+                summary,
+                url: entry.loc,
+                // Undated entries (0) get their first-seen time at ingest
+                timestamp: entry.published.or(entry.lastmod).unwrap_or(0),
+                is_read: false,
+                is_saved: false,
+                fetched_at: 0,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_CHILD_SITEMAPS, MAX_ENTRIES, articles, parse};
+
+    fn index(children: &[(&str, &str)]) -> String {
+        let sitemaps: String = children
+            .iter()
+            .map(|(loc, lastmod)| {
+                format!("<sitemap><loc>{loc}</loc><lastmod>{lastmod}</lastmod></sitemap>")
+            })
+            .collect();
+        format!(
+            r#"<?xml version="1.0"?><sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{sitemaps}</sitemapindex>"#
+        )
+    }
+
+    #[test]
+    fn index_follows_newest_children() {
+        let xml = index(&[
+            ("https://example.com/a.xml", "2024-01-01"),
+            ("https://example.com/b.xml", "2024-04-01"),
+            ("https://example.com/c.xml", "2024-02-01"),
+            ("https://example.com/d.xml", "2024-03-01"),
+            ("https://example.com/e.xml", "2023-12-01"),
+        ]);
+        let found = parse(xml.as_bytes()).unwrap();
+        assert!(found.is_index());
+        assert!(found.urls.is_empty());
+        assert_eq!(
+            found.newest_sitemaps(MAX_CHILD_SITEMAPS),
+            [
+                "https://example.com/b.xml",
+                "https://example.com/d.xml",
+                "https://example.com/c.xml",
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_index_lists_no_pages() {
+        // A child that is itself an index adds no entries, so indexes are only
+        // followed one level deep
+        let child = index(&[("https://example.com/deeper.xml", "2024-01-01")]);
+        let child = parse(child.as_bytes()).unwrap();
+        assert!(child.is_index());
+        assert!(child.urls.is_empty());
+    }
+
+    #[test]
+    fn news_entries_and_entities() {
+        let xml = r#"<?xml version="1.0"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9"
+                    xmlns:news="http://www.google.com/schemas/sitemap-news/0.9"
+                    xmlns:image="http://www.google.com/schemas/sitemap-image/1.1">
+              <url>
+                <loc>https://example.com/story?id=1&amp;lang=en</loc>
+                <news:news>
+                  <news:publication_date>2024-05-01T10:00:00Z</news:publication_date>
+                  <news:title>Headline</news:title>
+                </news:news>
+                <image:image>
+                  <image:loc>https://example.com/photo.jpg</image:loc>
+                  <image:title>Photo caption</image:title>
+                </image:image>
+              </url>
+            </urlset>"#;
+        let found = parse(xml.as_bytes()).unwrap();
+        assert!(found.is_news());
+        let entry = &found.urls[0];
+        assert_eq!(entry.loc, "https://example.com/story?id=1&lang=en");
+        assert_eq!(entry.title.as_deref(), Some("Headline"));
+        assert_eq!(
+            entry.image.as_deref(),
+            Some("https://example.com/photo.jpg")
+        );
+        assert!(entry.published.is_some());
+    }
+
+    #[test]
+    fn rejects_other_xml() {
+        assert!(parse(b"<rss><channel/></rss>").is_err());
+        assert!(parse(b"<urlset/>").unwrap().urls.is_empty());
+    }
+
+    #[test]
+    fn articles_are_deduplicated_newest_first_and_capped() {
+        let urls: String = (0..MAX_ENTRIES + 10)
+            .map(|i| {
+                format!(
+                    "<url><loc>https://example.com/p{}</loc><lastmod>2024-01-{:02}</lastmod></url>",
+                    i % (MAX_ENTRIES + 5),
+                    i % 28 + 1
+                )
+            })
+            .collect();
+        let xml = format!("<urlset>{urls}</urlset>");
+        let found = parse(xml.as_bytes()).unwrap();
+        let articles = articles(found.urls, None);
+        assert_eq!(articles.len(), MAX_ENTRIES);
+        let unique: std::collections::HashSet<_> = articles.iter().map(|a| &a.url).collect();
+        assert_eq!(unique.len(), MAX_ENTRIES);
+        assert!(
+            articles
+                .windows(2)
+                .all(|w| w[0].timestamp >= w[1].timestamp)
+        );
+    }
+}
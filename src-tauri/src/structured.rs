@@ -0,0 +1,318 @@
+use crate::models::Article;
+use crate::sanitize::escape;
+use crate::scrape::parse_date;
+use scraper::{ElementRef, Html, Selector};
+use serde_json::Value;
+use std::collections::HashMap;
+use url::Url;
+
+/// schema.org types treated as an article.
+const ARTICLE_TYPES: [&str; 5] = [
+    "Article",
+    "BlogPosting",
+    "NewsArticle",
+    "TechArticle",
+    "Report",
+];
+
+/// What a page says about an article, gathered from JSON-LD, microdata,
+/// OpenGraph or `<time>` elements. Earlier sources win.
+#[derive(Debug, Default, Clone)]
+pub struct ArticleMeta {
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub published: Option<i64>,
+    pub author: Option<String>,
+    pub image: Option<String>,
+    pub description: Option<String>,
+}
+
+impl ArticleMeta {
+    /// Fills fields still missing here from `other`.
+    fn merge(&mut self, other: ArticleMeta) {
+        self.url = self.url.take().or(other.url);
+        self.title = self.title.take().or(other.title);
+        self.published = self.published.or(other.published);
+        self.author = self.author.take().or(other.author);
+        self.image = self.image.take().or(other.image);
+        self.description = self.description.take().or(other.description);
+    }
+
+    /// Fills what the scraper couldn't find; nothing the page listed directly is
+    /// overwritten. Image and description become the summary when there is none.
+    pub fn apply(&self, article: &mut Article) {
+        if let Some(title) = &self.title
+            && (article.title.is_empty() || article.title == article.url)
+        {
+            article.title = title.clone();
+        }
+        if article.timestamp == 0 {
+            article.timestamp = self.published.unwrap_or(0);
+        }
+        if article.author.is_empty()
+            && let Some(author) = &self.author
+        {
+            article.author = author.clone();
+        }
+        if article.summary.is_empty() {
+            let mut summary = String::new();
+            if let Some(image) = &self.image {
+                summary.push_str(&format!("<p><img src=\"{}\"></p>", escape(image)));
+            }
+            if let Some(description) = &self.description {
+                summary.push_str(&format!("<p>{}</p>", escape(description)));
+            }
+            article.summary = summary;
+        }
+    }
+}
+
+/// Articles a listing page describes in JSON-LD (including `ItemList`s) or
+/// microdata, keyed by absolute URL.
+pub fn listing_metadata(html: &str, base: &Url) -> HashMap<String, ArticleMeta> {
+    let document = Html::parse_document(html);
+    let mut found: HashMap<String, ArticleMeta> = HashMap::new();
+    let items = json_ld(&document, base)
+        .into_iter()
+        .chain(microdata(&document, base));
+    for meta in items {
+        let Some(url) = meta.url.clone() else {
+            continue;
+        };
+        match found.get_mut(&url) {
+            Some(existing) => existing.merge(meta),
+            None => {
+                found.insert(url, meta);
+            },
+        }
+    }
+    found
+}
+
+/// Metadata for a single article page: its JSON-LD or microdata article,
+/// then OpenGraph and `<meta>` tags, then the first `<time datetime>`.
+pub fn page_metadata(html: &str, base: &Url) -> ArticleMeta {
+    let document = Html::parse_document(html);
+    let mut meta = ArticleMeta::default();
+    for item in json_ld(&document, base)
+        .into_iter()
+        .chain(microdata(&document, base))
+    {
+        meta.merge(item);
+    }
+    meta.merge(open_graph(&document, base));
+    if meta.published.is_none() {
+        meta.published = select_first(&document, "time[datetime]")
+            .and_then(|el| el.value().attr("datetime"))
+            .and_then(parse_date);
+    }
+    meta
+}
+
+fn select_first<'a>(document: &'a Html, css: &str) -> Option<ElementRef<'a>> {
+    let selector = Selector::parse(css).ok()?;
+    document.select(&selector).next()
+}
+
+fn json_ld(document: &Html, base: &Url) -> Vec<ArticleMeta> {
+    let Ok(selector) = Selector::parse("script[type='application/ld+json']") else {
+        return vec![];
+    };
+    let mut found = Vec::new();
+    for script in document.select(&selector) {
+        let text = script.text().collect::<String>();
+        match serde_json::from_str::<Value>(text.trim()) {
+            Ok(value) => collect_json_ld(&value, base, &mut found),
+            Err(e) => log::debug!("structured: invalid JSON-LD: {}", e),
+        }
+    }
+    found
+}
+
+/// Walks arrays, `@graph` and `ItemList`s looking for article objects.
+fn collect_json_ld(value: &Value, base: &Url, found: &mut Vec<ArticleMeta>) {
+    match value {
+        Value::Array(values) => {
+            for v in values {
+                collect_json_ld(v, base, found);
+            }
+        },
+        Value::Object(object) => {
+            if let Some(graph) = object.get("@graph") {
+                collect_json_ld(graph, base, found);
+            }
+            if has_type(value, &["ItemList"]) {
+                for element in list(value.get("itemListElement")) {
+                    match element.get("item") {
+                        // ListItem wrapping the article, or just naming its URL
+                        Some(item @ Value::Object(_)) => collect_json_ld(item, base, found),
+                        Some(Value::String(url)) => found.push(ArticleMeta {
+                            url: absolute(base, url),
+                            title: string(element.get("name")),
+                            ..Default::default()
+                        }),
+                        _ if has_type(element, &ARTICLE_TYPES) => {
+                            collect_json_ld(element, base, found)
+                        },
+                        _ => found.push(ArticleMeta {
+                            url: string(element.get("url")).and_then(|u| absolute(base, &u)),
+                            title: string(element.get("name")),
+                            ..Default::default()
+                        }),
+                    }
+                }
+            }
+            if has_type(value, &ARTICLE_TYPES) {
+                found.push(json_ld_article(value, base));
+            }
+        },
+        _ => {},
+    }
+}
+
+fn json_ld_article(value: &Value, base: &Url) -> ArticleMeta {
+    let url = string(value.get("url"))
+        .or_else(|| match value.get("mainEntityOfPage") {
+            Some(Value::Object(page)) => string(page.get("@id")),
+            other => string(other),
+        })
+        .and_then(|u| absolute(base, &u));
+    let authors = list(value.get("author"))
+        .filter_map(|a| match a {
+            Value::Object(_) => string(a.get("name")),
+            other => string(Some(other)),
+        })
+        .collect::<Vec<_>>();
+    let image = list(value.get("image"))
+        .find_map(|i| match i {
+            Value::Object(_) => string(i.get("url")),
+            other => string(Some(other)),
+        })
+        .and_then(|u| absolute(base, &u));
+    ArticleMeta {
+        url,
+        title: string(value.get("headline")).or_else(|| string(value.get("name"))),
+        published: string(value.get("datePublished"))
+            .or_else(|| string(value.get("dateCreated")))
+            .and_then(|d| parse_date(&d)),
+        author: Some(authors.join(", ")).filter(|a| !a.is_empty()),
+        image,
+        description: string(value.get("description")),
+    }
+}
+
+/// `@type` may be a single name or a list of them.
+fn has_type(value: &Value, types: &[&str]) -> bool {
+    list(value.get("@type")).any(|t| t.as_str().is_some_and(|t| types.contains(&t)))
+}
+
+/// A JSON-LD property as a sequence, whether it holds one value or an array.
+fn list(value: Option<&Value>) -> impl Iterator<Item = &Value> {
+    let values: &[Value] = match value {
+        Some(Value::Array(values)) => values,
+        Some(value) => std::slice::from_ref(value),
+        None => &[],
+    };
+    values.iter()
+}
+
+fn string(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(Value::as_str)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn absolute(base: &Url, url: &str) -> Option<String> {
+    base.join(url.trim()).ok().map(|u| u.to_string())
+}
+
+fn microdata(document: &Html, base: &Url) -> Vec<ArticleMeta> {
+    let Ok(selector) = Selector::parse("[itemscope][itemtype]") else {
+        return vec![];
+    };
+    document
+        .select(&selector)
+        .filter(|el| {
+            let itemtype = el.value().attr("itemtype").unwrap_or_default();
+            ARTICLE_TYPES
+                .iter()
+                .any(|t| itemtype.trim_end_matches('/').ends_with(&format!("/{}", t)))
+        })
+        .map(|item| {
+            let prop = |name: &str| {
+                let selector = Selector::parse(&format!("[itemprop~='{}']", name)).ok()?;
+                item.select(&selector).next()
+            };
+            let author = prop("author").and_then(|el| {
+                let name = Selector::parse("[itemprop~='name']")
+                    .ok()
+                    .and_then(|s| el.select(&s).next())
+                    .unwrap_or(el);
+                prop_value(name)
+            });
+            ArticleMeta {
+                url: prop("url")
+                    .and_then(prop_value)
+                    .or_else(|| item.value().attr("itemid").map(str::to_string))
+                    .and_then(|u| absolute(base, &u)),
+                title: prop("headline")
+                    .or_else(|| prop("name"))
+                    .and_then(prop_value),
+                published: prop("datePublished")
+                    .and_then(prop_value)
+                    .and_then(|d| parse_date(&d)),
+                author,
+                image: prop("image")
+                    .and_then(prop_value)
+                    .and_then(|u| absolute(base, &u)),
+                description: prop("description").and_then(prop_value),
+            }
+        })
+        .collect()
+}
+
+/// A microdata property's value, per the attribute its element carries it in.
+fn prop_value(el: ElementRef) -> Option<String> {
+    let attrs = el.value();
+    let value = attrs
+        .attr("content")
+        .or_else(|| attrs.attr("datetime"))
+        .or_else(|| match attrs.name() {
+            "a" | "link" | "area" => attrs.attr("href"),
+            "img" | "audio" | "video" | "source" => attrs.attr("src"),
+            _ => None,
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            el.text()
+                .flat_map(str::split_whitespace)
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+    Some(value.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn open_graph(document: &Html, base: &Url) -> ArticleMeta {
+    let meta = |names: &[&str]| {
+        names.iter().find_map(|name| {
+            select_first(
+                document,
+                &format!("meta[property='{0}'], meta[name='{0}']", name),
+            )
+            .and_then(|el| el.value().attr("content"))
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+        })
+    };
+    ArticleMeta {
+        url: meta(&["og:url"]).and_then(|u| absolute(base, &u)),
+        title: meta(&["og:title", "twitter:title"]),
+        published: meta(&["article:published_time", "og:published_time", "date"])
+            .and_then(|d| parse_date(&d)),
+        // article:author is often a profile URL rather than a name
+        author: meta(&["author", "article:author"]).filter(|a| !a.starts_with("http")),
+        image: meta(&["og:image", "twitter:image"]).and_then(|u| absolute(base, &u)),
+        description: meta(&["og:description", "description", "twitter:description"]),
+    }
+}
@@ -0,0 +1,360 @@
+use url::Url;
+
+/// Maps a page URL on one platform to that page's native feed, or `None` if the
+/// URL isn't one it recognises.
+type Transformer = fn(&Url) -> Option<String>;
+
+/// Tried in order; the first match wins. Add a platform by writing a transformer
+/// and listing it here. Mastodon matches any host, so it stays last.
+const TRANSFORMERS: [(&str, Transformer); 7] = [
+    ("youtube", youtube),
+    ("reddit", reddit),
+    ("github", github),
+    ("medium", medium),
+    ("substack", substack),
+    ("hacker_news", hacker_news),
+    ("mastodon", mastodon),
+];
+
+/// The native feed URL for a profile, channel or repository page, if `url` is on
+/// a platform that publishes one at a predictable address.
+pub fn feed_url(url: &str) -> Option<String> {
+    let parsed = Url::parse(url).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return None;
+    }
+    TRANSFORMERS.iter().find_map(|(name, transform)| {
+        let feed = transform(&parsed)?;
+        log::debug!("transformers: {} rewrote {} to {}", name, url, feed);
+        Some(feed)
+    })
+}
+
+/// The host without a leading `www.`, lowercased.
+fn host(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+    match host.strip_prefix("www.") {
+        Some(rest) => rest.to_string(),
+        None => host,
+    }
+}
+
+fn segments(url: &Url) -> Vec<&str> {
+    url.path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+fn query_param(url: &Url, name: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, v)| k == name && !v.is_empty())
+        .map(|(_, v)| v.into_owned())
+}
+
+/// Channels, legacy usernames and playlists. `@handle` pages don't carry the
+/// channel id in the URL; their `<link rel="alternate">` is found by discovery.
+fn youtube(url: &Url) -> Option<String> {
+    const FEEDS: &str = "https://www.youtube.com/feeds/videos.xml";
+    if !matches!(host(url).as_str(), "youtube.com" | "m.youtube.com") {
+        return None;
+    }
+    if let Some(list) = query_param(url, "list") {
+        return Some(format!("{}?playlist_id={}", FEEDS, list));
+    }
+    match segments(url).as_slice() {
+        ["channel", id, ..] => Some(format!("{}?channel_id={}", FEEDS, id)),
+        ["user", name, ..] => Some(format!("{}?user={}", FEEDS, name)),
+        _ => None,
+    }
+}
+
+/// Subreddits and user pages, on any of Reddit's front ends.
+fn reddit(url: &Url) -> Option<String> {
+    if !matches!(
+        host(url).as_str(),
+        "reddit.com" | "old.reddit.com" | "new.reddit.com" | "m.reddit.com"
+    ) {
+        return None;
+    }
+    match segments(url).as_slice() {
+        ["r", sub, rest @ ..] if !rest.iter().any(|s| s.ends_with(".rss")) => {
+            Some(format!("https://www.reddit.com/r/{}/.rss", sub))
+        },
+        ["user" | "u", name, rest @ ..] if !rest.iter().any(|s| s.ends_with(".rss")) => {
+            Some(format!("https://www.reddit.com/user/{}/.rss", name))
+        },
+        _ => None,
+    }
+}
+
+/// Top-level GitHub paths that aren't users or organisations.
+const GITHUB_RESERVED: [&str; 12] = [
+    "about",
+    "explore",
+    "features",
+    "login",
+    "marketplace",
+    "notifications",
+    "orgs",
+    "pricing",
+    "pulls",
+    "search",
+    "settings",
+    "topics",
+];
+
+/// A repository's front page and releases map to its releases, its tags and
+/// commits pages to those feeds, and a bare user or organisation to its public
+/// activity. Deeper pages such as issues or pull requests have no feed.
+fn github(url: &Url) -> Option<String> {
+    if host(url) != "github.com" {
+        return None;
+    }
+    let segments = segments(url);
+    if segments.iter().any(|s| s.ends_with(".atom")) {
+        return None;
+    }
+    let base = "https://github.com";
+    match segments.as_slice() {
+        [owner] if !GITHUB_RESERVED.contains(owner) => Some(format!("{}/{}.atom", base, owner)),
+        [owner, _, ..] if GITHUB_RESERVED.contains(owner) => None,
+        [owner, repo, "tags", ..] => Some(format!("{}/{}/{}/tags.atom", base, owner, repo)),
+        [owner, repo, "commits"] => Some(format!("{}/{}/{}/commits.atom", base, owner, repo)),
+        [owner, repo, "commits", branch @ ..] => Some(format!(
+            "{}/{}/{}/commits/{}.atom",
+            base,
+            owner,
+            repo,
+            branch.join("/")
+        )),
+        [owner, repo] | [owner, repo, "releases"] => {
+            Some(format!("{}/{}/{}/releases.atom", base, owner, repo))
+        },
+        _ => None,
+    }
+}
+
+/// Profiles, publications and tags on medium.com, plus `name.medium.com` blogs.
+fn medium(url: &Url) -> Option<String> {
+    let host = host(url);
+    if let Some(name) = host.strip_suffix(".medium.com") {
+        return Some(format!("https://{}.medium.com/feed", name));
+    }
+    if host != "medium.com" {
+        return None;
+    }
+    match segments(url).as_slice() {
+        ["feed", ..] => None,
+        ["tag", tag, ..] => Some(format!("https://medium.com/feed/tag/{}", tag)),
+        [first, ..] if !matches!(*first, "m" | "me" | "search" | "plans") => {
+            Some(format!("https://medium.com/feed/{}", first))
+        },
+        _ => None,
+    }
+}
+
+/// Any page of a `name.substack.com` newsletter. Custom domains are left to discovery.
+fn substack(url: &Url) -> Option<String> {
+    let name = host(url).strip_suffix(".substack.com")?.to_string();
+    if segments(url).first() == Some(&"feed") {
+        return None;
+    }
+    Some(format!("https://{}.substack.com/feed", name))
+}
+
+/// User pages go through hnrss.org, which Hacker News itself doesn't offer.
+fn hacker_news(url: &Url) -> Option<String> {
+    if host(url) != "news.ycombinator.com" {
+        return None;
+    }
+    match (url.path(), query_param(url, "id")) {
+        ("/user" | "/submitted", Some(id)) => {
+            Some(format!("https://hnrss.org/submitted?id={}", id))
+        },
+        ("/threads", Some(id)) => Some(format!("https://hnrss.org/threads?id={}", id)),
+        ("/" | "/news", None) => Some("https://news.ycombinator.com/rss".to_string()),
+        _ => None,
+    }
+}
+
+/// `https://instance/@user` profiles. Instances can be on any host, so this only
+/// looks at the path shape; hosts handled above never reach it.
+fn mastodon(url: &Url) -> Option<String> {
+    if matches!(
+        host(url).as_str(),
+        "youtube.com" | "m.youtube.com" | "medium.com"
+    ) {
+        return None;
+    }
+    let user = match segments(url).as_slice() {
+        [profile] => profile.strip_prefix('@')?,
+        _ => return None,
+    };
+    if user.is_empty() || user.ends_with(".rss") {
+        return None;
+    }
+    let host = url.host_str()?;
+    let port = url.port().map(|p| format!(":{}", p)).unwrap_or_default();
+    Some(format!("{}://{}{}/@{}.rss", url.scheme(), host, port, user))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::feed_url;
+
+    fn check(input: &str, expected: Option<&str>) {
+        assert_eq!(feed_url(input).as_deref(), expected, "input: {}", input);
+    }
+
+    #[test]
+    fn youtube() {
+        check(
+            "https://www.youtube.com/channel/UC_x5XG1OV2P6uZZ5FSM9Ttw",
+            Some("https://www.youtube.com/feeds/videos.xml?channel_id=UC_x5XG1OV2P6uZZ5FSM9Ttw"),
+        );
+        check(
+            "https://youtube.com/channel/UC_x5XG1OV2P6uZZ5FSM9Ttw/videos",
+            Some("https://www.youtube.com/feeds/videos.xml?channel_id=UC_x5XG1OV2P6uZZ5FSM9Ttw"),
+        );
+        check(
+            "https://www.youtube.com/playlist?list=PL590L5WQmH8fJ54F369BLDSqIwcs-TCfs",
+            Some(
+                "https://www.youtube.com/feeds/videos.xml?playlist_id=PL590L5WQmH8fJ54F369BLDSqIwcs-TCfs",
+            ),
+        );
+        check(
+            "https://m.youtube.com/user/GoogleDevelopers",
+            Some("https://www.youtube.com/feeds/videos.xml?user=GoogleDevelopers"),
+        );
+        check("https://www.youtube.com/@GoogleDevelopers", None);
+        check("https://www.youtube.com/watch?v=dQw4w9WgXcQ", None);
+    }
+
+    #[test]
+    fn reddit() {
+        check(
+            "https://www.reddit.com/r/rust",
+            Some("https://www.reddit.com/r/rust/.rss"),
+        );
+        check(
+            "https://old.reddit.com/r/rust/comments/abc/title/",
+            Some("https://www.reddit.com/r/rust/.rss"),
+        );
+        check(
+            "https://reddit.com/u/spez",
+            Some("https://www.reddit.com/user/spez/.rss"),
+        );
+        check("https://www.reddit.com/r/rust/.rss", None);
+        check("https://www.reddit.com/", None);
+    }
+
+    #[test]
+    fn github() {
+        check(
+            "https://github.com/rust-lang/rust",
+            Some("https://github.com/rust-lang/rust/releases.atom"),
+        );
+        check(
+            "https://github.com/rust-lang/rust/releases",
+            Some("https://github.com/rust-lang/rust/releases.atom"),
+        );
+        check(
+            "https://github.com/rust-lang/rust/tags",
+            Some("https://github.com/rust-lang/rust/tags.atom"),
+        );
+        check(
+            "https://github.com/rust-lang/rust/commits",
+            Some("https://github.com/rust-lang/rust/commits.atom"),
+        );
+        check(
+            "https://github.com/rust-lang/rust/commits/master",
+            Some("https://github.com/rust-lang/rust/commits/master.atom"),
+        );
+        check(
+            "https://github.com/torvalds",
+            Some("https://github.com/torvalds.atom"),
+        );
+        check("https://github.com/rust-lang/rust/releases.atom", None);
+        check("https://github.com/rust-lang/rust/issues/1", None);
+        check("https://github.com/rust-lang/rust/pull/2", None);
+        check(
+            "https://github.com/rust-lang/rust/blob/master/README.md",
+            None,
+        );
+        check("https://github.com/settings", None);
+        check("https://github.com/orgs/rust-lang/people", None);
+    }
+
+    #[test]
+    fn mastodon() {
+        check(
+            "https://mastodon.social/@Gargron",
+            Some("https://mastodon.social/@Gargron.rss"),
+        );
+        check(
+            "https://fosstodon.org/@rust/",
+            Some("https://fosstodon.org/@rust.rss"),
+        );
+        check("https://mastodon.social/@Gargron/109", None);
+        check("https://mastodon.social/@Gargron.rss", None);
+        check("https://example.com/about", None);
+    }
+
+    #[test]
+    fn medium() {
+        check(
+            "https://medium.com/@ev",
+            Some("https://medium.com/feed/@ev"),
+        );
+        check(
+            "https://medium.com/@ev/some-post-1a2b3c",
+            Some("https://medium.com/feed/@ev"),
+        );
+        check(
+            "https://medium.com/better-programming",
+            Some("https://medium.com/feed/better-programming"),
+        );
+        check(
+            "https://medium.com/tag/rust",
+            Some("https://medium.com/feed/tag/rust"),
+        );
+        check(
+            "https://someone.medium.com/a-post",
+            Some("https://someone.medium.com/feed"),
+        );
+        check("https://medium.com/feed/@ev", None);
+    }
+
+    #[test]
+    fn substack() {
+        check(
+            "https://astralcodexten.substack.com/p/some-post",
+            Some("https://astralcodexten.substack.com/feed"),
+        );
+        check("https://astralcodexten.substack.com/feed", None);
+    }
+
+    #[test]
+    fn hacker_news() {
+        check(
+            "https://news.ycombinator.com/user?id=pg",
+            Some("https://hnrss.org/submitted?id=pg"),
+        );
+        check(
+            "https://news.ycombinator.com/threads?id=pg",
+            Some("https://hnrss.org/threads?id=pg"),
+        );
+        check(
+            "https://news.ycombinator.com/",
+            Some("https://news.ycombinator.com/rss"),
+        );
+        check("https://news.ycombinator.com/item?id=1", None);
+    }
+
+    #[test]
+    fn ignores_other_urls() {
+        check("https://example.com/blog/", None);
+        check("ftp://github.com/rust-lang/rust", None);
+        check("not a url", None);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::error::{FeedMeeError, Result};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::redirect;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use url::{Host, Url};
+
+/// Redirect hops followed before a request fails.
+pub const MAX_REDIRECTS: usize = 5;
+
+const ALLOWED_SCHEMES: [&str; 2] = ["http", "https"];
+
+/// Rules every outbound request is checked against. Feeds, article pages and
+/// discovered URLs all come from untrusted content, so by default only public
+/// http(s) hosts are reachable; a feed can opt in to its local network.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UrlPolicy {
+    pub allow_private_network: bool,
+}
+
+impl UrlPolicy {
+    pub const PUBLIC: Self = Self {
+        allow_private_network: false,
+    };
+
+    pub fn for_feed(allow_private_network: bool) -> Self {
+        Self {
+            allow_private_network,
+        }
+    }
+
+    /// Parses `url` and rejects disallowed schemes and private hosts. Hostnames are
+    /// checked again once resolved (see `PublicResolver`).
+    pub fn check(&self, url: &str) -> Result<Url> {
+        let parsed = Url::parse(url)?;
+        self.check_url(&parsed)
+            .map_err(FeedMeeError::InvalidInput)?;
+        Ok(parsed)
+    }
+
+    fn check_url(&self, url: &Url) -> std::result::Result<(), String> {
+        if !ALLOWED_SCHEMES.contains(&url.scheme()) {
+            return Err(format!(
+                "Blocked {}: only http and https are allowed",
+                url.scheme()
+            ));
+        }
+        if self.allow_private_network {
+            return Ok(());
+        }
+        let private = match url.host() {
+            Some(Host::Ipv4(ip)) => is_private_ip(IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => is_private_ip(IpAddr::V6(ip)),
+            Some(Host::Domain(domain)) => is_local_name(domain),
+            None => true,
+        };
+        if private {
+            return Err(format!(
+                "Blocked {}: private network addresses must be allowed in the feed's settings",
+                url.host_str().unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Follows at most `MAX_REDIRECTS` hops, each of which must pass the policy.
+    pub fn redirect_policy(self) -> redirect::Policy {
+        redirect::Policy::custom(move |attempt| {
+            if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error(format!("More than {} redirects", MAX_REDIRECTS))
+            } else if let Err(e) = self.check_url(attempt.url()) {
+                attempt.error(e)
+            } else {
+                attempt.follow()
+            }
+        })
+    }
+}
+
+/// Loopback, private, link-local, CGNAT, multicast and other non-public ranges.
+pub fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_private_v4(v4),
+            None => is_private_v6(ip),
+        },
+    }
+}
+
+fn is_private_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b)) // shared address space (CGNAT)
+        || (a == 198 && (b == 18 || b == 19)) // benchmarking
+        || a >= 240 // reserved
+}
+
+fn is_private_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00 // unique local
+        || (first & 0xffc0) == 0xfe80 // link local
+}
+
+fn is_local_name(domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    domain == "localhost" || domain.ends_with(".localhost") || domain.ends_with(".local")
+}
+
+/// Resolves hostnames for the default client and refuses any that point into a
+/// private range, so a public-looking name can't be used to reach the LAN.
+///
+/// With a proxy configured the proxy resolves target hosts, so only the literal
+/// checks in `UrlPolicy::check` apply.
+pub struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .collect::<Vec<_>>();
+            if addrs.iter().any(|a| is_private_ip(a.ip())) {
+                return Err(
+                    format!("Blocked {}: it resolves to a private network address", host).into(),
+                );
+            }
+            let addrs: Addrs = Box::new(addrs.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{UrlPolicy, is_private_ip};
+    use std::net::IpAddr;
+
+    fn private(ip: &str) -> bool {
+        is_private_ip(ip.parse::<IpAddr>().unwrap())
+    }
+
+    #[test]
+    fn private_and_special_v4() {
+        for ip in [
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "127.0.0.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "224.0.0.1",
+        ] {
+            assert!(private(ip), "{ip}");
+        }
+        for ip in ["8.8.8.8", "93.184.216.34", "100.128.0.1", "172.32.0.1"] {
+            assert!(!private(ip), "{ip}");
+        }
+    }
+
+    #[test]
+    fn private_and_special_v6() {
+        for ip in ["::1", "::", "fe80::1", "fd00::1", "ff02::1"] {
+            assert!(private(ip), "{ip}");
+        }
+        assert!(!private("2606:4700:4700::1111"));
+    }
+
+    #[test]
+    fn ipv4_mapped_v6_uses_v4_rules() {
+        assert!(private("::ffff:127.0.0.1"));
+        assert!(private("::ffff:192.168.0.1"));
+        assert!(private("::ffff:169.254.169.254"));
+        assert!(!private("::ffff:8.8.8.8"));
+    }
+
+    #[test]
+    fn check_rejects_private_hosts_and_schemes() {
+        let public = UrlPolicy::PUBLIC;
+        assert!(public.check("https://example.com/feed").is_ok());
+        for url in [
+            "http://127.0.0.1/",
+            "http://[::1]/",
+            "http://[::ffff:10.0.0.1]/",
+            "http://localhost:8080/",
+            "http://printer.local/",
+            "http://169.254.169.254/latest/meta-data/",
+            "file:///etc/passwd",
+            "ftp://example.com/",
+        ] {
+            assert!(public.check(url).is_err(), "{url}");
+        }
+    }
+
+    #[test]
+    fn feeds_can_allow_private_network() {
+        let policy = UrlPolicy::for_feed(true);
+        assert!(policy.check("http://192.168.1.10/feed.xml").is_ok());
+        assert!(policy.check("file:///etc/passwd").is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use crate::error::{FeedMeeError, Result};
+use crate::models::WatchConfig;
+use crate::sanitize::escape;
+use crate::scrape::{parse_optional_selector, parse_selector};
+use scraper::{ElementRef, Html, Selector};
+
+/// Elements whose text is never part of a page's visible content.
+const SKIPPED_ELEMENTS: [&str; 6] = ["script", "style", "noscript", "template", "svg", "head"];
+
+/// Elements that start a new line of text.
+const BLOCK_ELEMENTS: [&str; 24] = [
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "li",
+    "main",
+    "nav",
+    "p",
+    "section",
+    "tr",
+];
+
+/// Above this many line comparisons a change is reported as a full replacement
+/// instead of a line-by-line diff.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// Changed lines shown in an article summary; the rest are only counted.
+const MAX_DIFF_LINES: usize = 400;
+
+/// One changed line between two snapshots of a watched page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Removed(String),
+    Added(String),
+}
+
+/// Checked when a watch feed is added or edited, like `scrape::validate`.
+pub fn validate(config: &WatchConfig) -> Result<()> {
+    Selectors::parse(config).map(|_| ())
+}
+
+struct Selectors {
+    region: Option<Selector>,
+    ignore: Vec<Selector>,
+}
+
+impl Selectors {
+    fn parse(config: &WatchConfig) -> Result<Self> {
+        Ok(Self {
+            region: parse_optional_selector(config.selector.as_deref())?,
+            ignore: config
+                .ignore_selectors
+                .iter()
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(parse_selector)
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// The visible text of the watched part of a page, one trimmed line per block,
+/// with ignored elements left out. Fails if the selector matches nothing, since
+/// a vanished region is worth reporting rather than diffing as empty.
+pub fn snapshot(html: &str, config: &WatchConfig) -> Result<String> {
+    let selectors = Selectors::parse(config)?;
+    let document = Html::parse_document(html);
+    let regions: Vec<ElementRef> = match &selectors.region {
+        Some(sel) => document.select(sel).collect(),
+        None => vec![document.root_element()],
+    };
+    if regions.is_empty() {
+        return Err(FeedMeeError::parse(format!(
+            "Watched selector {:?} matched nothing",
+            config.selector.as_deref().unwrap_or_default()
+        )));
+    }
+
+    let ignored: Vec<ElementRef> = selectors
+        .ignore
+        .iter()
+        .flat_map(|sel| document.select(sel))
+        .collect();
+    let mut text = String::new();
+    for region in regions {
+        collect_text(region, &ignored, &mut text);
+        text.push('\n');
+    }
+
+    Ok(text
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn collect_text(element: ElementRef, ignored: &[ElementRef], out: &mut String) {
+    let name = element.value().name();
+    if ignored.contains(&element) || SKIPPED_ELEMENTS.contains(&name) {
+        return;
+    }
+    let block = BLOCK_ELEMENTS.contains(&name);
+    if block {
+        out.push('\n');
+    }
+    for child in element.children() {
+        if let Some(text) = child.value().as_text() {
+            out.push_str(text);
+        } else if let Some(child) = ElementRef::wrap(child) {
+            collect_text(child, ignored, out);
+        }
+    }
+    if block {
+        out.push('\n');
+    }
+}
+
+/// Lines removed from `old` and added in `new`, in page order.
+pub fn diff(old: &str, new: &str) -> Vec<Change> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Only the differing middle needs the quadratic comparison
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old = &old[prefix..old.len() - suffix];
+    let new = &new[prefix..new.len() - suffix];
+
+    if old.len() * new.len() > MAX_DIFF_CELLS {
+        return old
+            .iter()
+            .map(|l| Change::Removed(l.to_string()))
+            .chain(new.iter().map(|l| Change::Added(l.to_string())))
+            .collect();
+    }
+
+    // Longest common subsequence table, filled from the end
+    let width = new.len() + 1;
+    let mut lcs = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut changes = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < old.len()
+            && (j == new.len() || lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+        {
+            changes.push(Change::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            changes.push(Change::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    changes
+}
+
+/// Characters on changed lines, compared against the noise threshold.
+pub fn changed_chars(changes: &[Change]) -> usize {
+    changes
+        .iter()
+        .map(|c| match c {
+            Change::Removed(line) | Change::Added(line) => line.chars().count(),
+        })
+        .sum()
+}
+
+/// A short description such as "3 lines added, 1 removed".
+pub fn describe(changes: &[Change]) -> String {
+    let added = changes
+        .iter()
+        .filter(|c| matches!(c, Change::Added(_)))
+        .count();
+    let removed = changes.len() - added;
+    let lines = |n: usize| if n == 1 { "line" } else { "lines" };
+    match (added, removed) {
+        (0, r) => format!("{} {} removed", r, lines(r)),
+        (a, 0) => format!("{} {} added", a, lines(a)),
+        (a, r) => format!("{} {} added, {} removed", a, lines(a), r),
+    }
+}
+
+/// The diff as HTML for an article summary: removed lines struck through,
+/// added lines underlined.
+pub fn render_diff(changes: &[Change]) -> String {
+    let mut html = String::from("<ul>");
+    for change in changes.iter().take(MAX_DIFF_LINES) {
+        match change {
+            Change::Removed(line) => {
+                html.push_str(&format!("<li><del>{}</del></li>", escape(line)))
+            },
+            Change::Added(line) => html.push_str(&format!("<li><ins>{}</ins></li>", escape(line))),
+        }
+    }
+    html.push_str("</ul>");
+    if changes.len() > MAX_DIFF_LINES {
+        html.push_str(&format!(
+            "<p>{} more changed lines not shown.</p>",
+            changes.len() - MAX_DIFF_LINES
+        ));
+    }
+    html
+}
+
+/// The first snapshot, shown as the feed's opening article.
+pub fn render_snapshot(text: &str) -> String {
+    text.lines()
+        .take(MAX_DIFF_LINES)
+        .map(|line| format!("<p>{}</p>", escape(line)))
+        .collect()
+}
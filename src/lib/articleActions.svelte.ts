@@ -1,55 +1,40 @@
 import { invoke } from '@tauri-apps/api/core';
 import type { AppState } from './storeTypes';
-import type { Article } from './types';
+import type { Article, ArticlePage, ArticleQuery } from './types';
 
 export function createArticleActions(state: AppState) {
-    async function fetchPage(page: number): Promise<Article[]> {
-        const offset = page * state.pageSize;
-        const sortDesc = state.sortOrder === 'desc';
+    async function fetchPage(cursor: string | null): Promise<ArticlePage | null> {
+        const query: ArticleQuery = {
+            cursor,
+            limit: state.pageSize,
+            sort: 'date',
+        };
 
         if (state.searchQuery.trim()) {
-            return await invoke('search_articles', {
-                query: state.searchQuery.trim(),
-                limit: state.pageSize,
-                offset,
-                sortDesc,
-            });
-        }
-
-        if (state.selectedFeedId === -1) {
-            const cutoff = Math.floor(Date.now() / 1000) - state.latestHours * 3600;
-            return await invoke('get_latest_articles', {
-                cutoffTimestamp: cutoff,
-                limit: state.pageSize,
-                offset,
-                sortDesc,
-            });
+            query.search = state.searchQuery.trim();
+        } else if (state.selectedFeedId === -1) {
+            query.since = Math.floor(Date.now() / 1000) - state.latestHours * 3600;
+            query.date_field = state.settings.latest_by_fetch_time ? 'fetched' : 'published';
         } else if (state.selectedFeedId === -2) {
-            return await invoke('get_saved_articles', { limit: state.pageSize, offset, sortDesc });
+            query.saved_only = true;
         } else if (state.selectedFeedId) {
-            return await invoke('get_articles_for_feed', {
-                feedId: state.selectedFeedId,
-                limit: state.pageSize,
-                offset,
-                sortDesc,
-            });
+            query.feed_id = state.selectedFeedId;
         } else if (state.selectedFolderId) {
-            return await invoke('get_articles_for_folder', {
-                folderId: state.selectedFolderId,
-                limit: state.pageSize,
-                offset,
-                sortDesc,
-            });
+            query.folder_id = state.selectedFolderId;
+        } else {
+            return null;
         }
-        return [];
+        return await invoke('query_articles', { query });
     }
 
     async function reloadCurrentArticleList() {
         state.articles = [];
-        state.page = 0;
-        const result = await fetchPage(0);
-        state.articles = result || [];
-        state.hasMore = (result?.length || 0) === state.pageSize;
+        state.cursor = null;
+        const result = await fetchPage(null);
+        state.articles = result?.articles || [];
+        state.cursor = result?.next_cursor ?? null;
+        state.hasMore = state.cursor !== null;
+        if (result) state.sortOrder = result.sort_asc ? 'asc' : 'desc';
     }
 
     async function loadMore() {
@@ -60,13 +45,12 @@ export function createArticleActions(state: AppState) {
         )
             return;
         state.isLoadingArticles = true;
-        const nextPage = state.page + 1;
         try {
-            const result = await fetchPage(nextPage);
-            if (result && result.length > 0) {
-                state.articles = [...state.articles, ...result];
-                state.page = nextPage;
-                state.hasMore = result.length === state.pageSize;
+            const result = await fetchPage(state.cursor);
+            if (result && result.articles.length > 0) {
+                state.articles = [...state.articles, ...result.articles];
+                state.cursor = result.next_cursor;
+                state.hasMore = result.next_cursor !== null;
             } else {
                 state.hasMore = false;
             }
@@ -80,6 +64,14 @@ export function createArticleActions(state: AppState) {
     function selectArticle(article: Article) {
         state.focusedPane = 'list';
         state.selectedArticle = article;
+        if (!article.is_read) {
+            state.getEffectiveFeedSettings(article.feed_id).then((feedSettings) => {
+                if (feedSettings?.mark_read_on_open ?? true) markRead(article);
+            });
+        }
+    }
+
+    function markRead(article: Article) {
         if (!article.is_read) {
             article.is_read = true;
             invoke('mark_article_read', { id: article.id, read: true }).catch(() => {
@@ -114,7 +106,10 @@ export function createArticleActions(state: AppState) {
 
     async function fetchFullContent(article: Article): Promise<string | null> {
         try {
-            return await invoke<string>('get_article_content', { url: article.url });
+            return await invoke<string>('get_article_content', {
+                url: article.url,
+                feedId: article.feed_id,
+            });
         } catch {
             return null;
         }
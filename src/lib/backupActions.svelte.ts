@@ -0,0 +1,56 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { AppState } from './storeTypes';
+import type { BackupInfo } from './types';
+import { errorMessage } from './utils/errors';
+
+export function createBackupActions(state: AppState) {
+    async function listBackups(): Promise<BackupInfo[]> {
+        try {
+            return await invoke<BackupInfo[]>('list_backups');
+        } catch (e) {
+            console.error('Failed to list backups:', e);
+            return [];
+        }
+    }
+
+    async function createBackup() {
+        try {
+            await invoke<BackupInfo>('create_backup');
+        } catch (e) {
+            state.alert(`Backup failed: ${errorMessage(e)}`);
+        }
+    }
+
+    function restoreBackup(name: string) {
+        state.confirm(`Restore ${name}? Current data is backed up first.`, async () => {
+            try {
+                await invoke('restore_backup', { name });
+                await state.refreshFolders();
+                await state.reloadCurrentArticleList();
+                state.alert('Restore successful!');
+            } catch (e) {
+                state.alert(`Restore failed: ${errorMessage(e)}`);
+            }
+        });
+    }
+
+    async function exportBackup(name: string) {
+        try {
+            if (await invoke<boolean>('export_backup', { name })) {
+                state.alert('Export successful!');
+            }
+        } catch (e) {
+            state.alert(`Failed to export backup: ${errorMessage(e)}`);
+        }
+    }
+
+    async function importBackup() {
+        try {
+            await invoke<BackupInfo | null>('import_backup');
+        } catch (e) {
+            state.alert(`Failed to import backup: ${errorMessage(e)}`);
+        }
+    }
+
+    return { listBackups, createBackup, restoreBackup, exportBackup, importBackup };
+}
@@ -1,6 +1,8 @@
 import { invoke } from '@tauri-apps/api/core';
-import { open, save } from '@tauri-apps/plugin-dialog';
+import { open } from '@tauri-apps/plugin-dialog';
 import type { AppState } from './storeTypes';
+import type { FeedCandidate, WatchConfig } from './types';
+import { errorMessage } from './utils/errors';
 
 export function createFeedActions(state: AppState) {
     async function markAllRead() {
@@ -21,18 +23,63 @@ export function createFeedActions(state: AppState) {
         }
     }
 
-    async function addFeed(url: string, folderId: number | null = null) {
+    async function addFeed(
+        url: string,
+        folderId: number | null = null,
+        allowPrivateNetwork = false,
+        watch: WatchConfig | null = null,
+    ) {
         state.isLoadingArticles = true;
         try {
-            await invoke('add_feed', { url, folderId });
+            // A watched page is followed as-is, so there is nothing to discover
+            if (watch) {
+                await invoke('add_feed', { url, folderId, allowPrivateNetwork, watch });
+                await state.refreshFolders();
+                return;
+            }
+            const candidates = await invoke<FeedCandidate[]>('discover_feeds', {
+                url,
+                allowPrivateNetwork,
+            });
+            if (candidates.length > 1) {
+                state.feedChoice = { url, folderId, allowPrivateNetwork, candidates };
+                return;
+            }
+            // No feed at all falls back to following the page as a website
+            const feedUrl = candidates[0]?.url ?? null;
+            await invoke('add_feed', { url, folderId, allowPrivateNetwork, feedUrl });
             await state.refreshFolders();
         } catch (e) {
-            state.alert(`Error adding feed: ${e}`);
+            state.alert(`Error adding feed: ${errorMessage(e)}`);
         } finally {
             state.isLoadingArticles = false;
         }
     }
 
+    async function chooseFeed(feedUrl: string) {
+        const choice = state.feedChoice;
+        if (!choice) return;
+        state.feedChoice = null;
+        state.isLoadingArticles = true;
+        try {
+            await invoke('add_feed', {
+                url: choice.url,
+                folderId: choice.folderId,
+                allowPrivateNetwork: choice.allowPrivateNetwork,
+                feedUrl,
+            });
+            await state.refreshFolders();
+        } catch (e) {
+            state.alert(`Error adding feed: ${errorMessage(e)}`);
+        } finally {
+            state.isLoadingArticles = false;
+        }
+    }
+
+    function cancelFeedChoice() {
+        state.feedChoice = null;
+    }
+
     async function createFolder(name: string) {
         try {
             await invoke('create_folder', { name });
@@ -62,18 +109,11 @@ export function createFeedActions(state: AppState) {
 
     async function exportOpml() {
         try {
-            const opmlContent = await invoke<string>('export_opml');
-            if (!opmlContent) return;
-            const filePath = await save({
-                filters: [{ name: 'OPML File', extensions: ['opml'] }],
-                defaultPath: 'feeds.opml',
-            });
-            if (filePath) {
-                await invoke('write_file', { path: filePath, content: opmlContent });
+            if (await invoke<boolean>('export_opml_to_path')) {
                 state.alert('Export successful!');
             }
         } catch (e) {
-            state.alert(`Failed to export OPML: ${e}`);
+            state.alert(`Failed to export OPML: ${errorMessage(e)}`);
         }
     }
 
@@ -96,7 +136,7 @@ export function createFeedActions(state: AppState) {
     }
 
     async function deleteFeed(id: number) {
-        state.confirm('Delete feed?', async () => {
+        state.confirm('Move feed to trash?', async () => {
             try {
                 await invoke('delete_feed', { id });
                 if (state.selectedFeedId === id) {
@@ -104,6 +144,7 @@ export function createFeedActions(state: AppState) {
                     state.articles = [];
                 }
                 await state.refreshFolders();
+                state.offerUndo('feed', id, 'Feed moved to trash');
             } catch (e) {
                 console.error(e);
             }
@@ -111,10 +152,11 @@ export function createFeedActions(state: AppState) {
     }
 
     async function deleteFolder(id: number) {
-        state.confirm('Delete folder and feeds?', async () => {
+        state.confirm('Move folder and its feeds to trash?', async () => {
             try {
                 await invoke('delete_folder', { id });
                 await state.refreshFolders();
+                state.offerUndo('folder', id, 'Folder moved to trash');
             } catch (e) {
                 console.error(e);
             }
@@ -133,6 +175,8 @@ export function createFeedActions(state: AppState) {
     return {
         markAllRead,
         addFeed,
+        chooseFeed,
+        cancelFeedChoice,
         createFolder,
         importOpml,
         exportOpml,
@@ -32,22 +32,37 @@ export function createFeedRefresher(state: AppState) {
     }
 
     async function refreshAllFeeds() {
-        const staleFeeds = state.folders
+        const staleIds = state.folders
             .flatMap((f) => f.feeds)
-            .filter((f) => !state.isFeedFresh(f.id));
-        if (staleFeeds.length === 0) return;
+            .filter((f) => !state.isFeedFresh(f.id))
+            .map((f) => f.id);
+        await refreshFeeds(staleIds);
+    }
+
+    // Scheduled refresh: the backend picks feeds whose own refresh interval has elapsed.
+    async function refreshDueFeeds() {
+        try {
+            const dueIds = await invoke<number[]>('get_due_feeds');
+            await refreshFeeds(dueIds.filter((id) => !state.isFeedUpdating(id)));
+        } catch (e) {
+            console.error('Failed to check for due feeds:', e);
+        }
+    }
+
+    async function refreshFeeds(feedIds: number[]) {
+        if (feedIds.length === 0) return;
 
         state.isRefreshingFeeds = true;
 
         const newSet = new Set(state.updatingFeedIds);
-        staleFeeds.forEach((f) => newSet.add(f.id));
+        feedIds.forEach((id) => newSet.add(id));
         state.updatingFeedIds = newSet;
 
         let index = 0;
         const worker = async () => {
-            while (index < staleFeeds.length) {
-                const feed = staleFeeds[index++];
-                await performSingleFeedRefresh(feed.id);
+            while (index < feedIds.length) {
+                const feedId = feedIds[index++];
+                await performSingleFeedRefresh(feedId);
             }
         };
 
@@ -126,5 +141,5 @@ export function createFeedRefresher(state: AppState) {
         }
     }
 
-    return { refreshAllFeeds, requestRefreshFeed, requestRefreshFolder };
+    return { refreshAllFeeds, refreshDueFeeds, requestRefreshFeed, requestRefreshFolder };
 }
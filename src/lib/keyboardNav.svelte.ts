@@ -110,7 +110,7 @@ export function registerShortcuts(state: AppState) {
 
 export function setupKeyHandler(state: AppState) {
     window.addEventListener('keydown', (e) => {
-        if (state.showSettings) return;
+        if (state.showSettings || state.showTrash) return;
 
         const tag = (e.target as HTMLElement)?.tagName?.toLowerCase();
         const isInput =
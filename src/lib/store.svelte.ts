@@ -1,17 +1,38 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { AppSettings, Article, Folder } from './types';
+import type {
+    AppSettings,
+    Article,
+    BackupInfo,
+    FeedSettings,
+    Folder,
+    PendingFeedChoice,
+    ResolvedFeedSettings,
+    TrashItem,
+    WatchConfig,
+} from './types';
 import { shortcutManager } from './utils/shortcuts';
+import { errorMessage, isAppError } from './utils/errors';
 import { createFeedRefresher } from './feedRefresh.svelte';
 import { createFeedActions } from './feedActions.svelte';
 import { createArticleActions } from './articleActions.svelte';
+import { createBackupActions } from './backupActions.svelte';
+import { createTrashActions } from './trashActions.svelte';
 import { registerShortcuts, setupKeyHandler } from './keyboardNav.svelte';
-import type { AppState, Theme, SortOrder } from './storeTypes';
+import type { AppState, Theme, SortOrder, UndoDelete } from './storeTypes';
 
 export type { AppState } from './storeTypes';
 export type { Theme, SortOrder };
 export type { Article };
 export const FEED_ID_LATEST = -1;
 export const FEED_ID_SAVED = -2;
+// Matches the backend's defaults for a newly watched page.
+export const DEFAULT_WATCH_CONFIG: WatchConfig = {
+    selector: null,
+    ignore_selectors: [],
+    min_changed_chars: 10,
+};
+// Feeds carry their own refresh intervals, so check often and let the backend pick.
+const DUE_CHECK_INTERVAL_MS = 5 * 60 * 1000;
 
 class AppStateImpl {
     folders = $state<Folder[]>([]);
@@ -34,17 +55,35 @@ class AppStateImpl {
         default_view_id: -1,
         auto_collapse_folders: true,
         mark_feed_read_on_exit: false,
+        latest_by_fetch_time: false,
+        trash_retention_days: 30,
+        keep_saved_on_delete: true,
+        backup_interval_hours: 24,
+        backup_keep_count: 7,
+        article_retention_days: 0,
+        full_text: false,
+        reader_mode: false,
+        mark_read_on_open: true,
+        sort_order: 'desc',
     });
 
     showSettings = $state(false);
+    showTrash = $state(false);
+    undoDelete = $state<UndoDelete | null>(null);
+    settingsError = $state<string | null>(null);
+    feedSettingsTarget = $state<{ type: 'feed' | 'folder'; id: number; name: string } | null>(
+        null,
+    );
+    private effectiveFeedSettings = new Map<number, ResolvedFeedSettings>();
     showAddDialog = $state(false);
+    feedChoice = $state<PendingFeedChoice | null>(null);
     expandedFolders = $state<Set<number>>(new Set());
     focusedPane = $state<'nav' | 'list' | 'reading'>('nav');
     customShortcuts = $state<Record<string, string>>({});
     navWidth = $state(280);
     listWidth = $state(320);
 
-    page = 0;
+    cursor: string | null = null;
     readonly pageSize = 50;
     hasMore = $state(true);
     latestHours = $state(24);
@@ -67,11 +106,15 @@ class AppStateImpl {
     private refresh: ReturnType<typeof createFeedRefresher>;
     private feedOps: ReturnType<typeof createFeedActions>;
     private articleOps: ReturnType<typeof createArticleActions>;
+    private backupOps: ReturnType<typeof createBackupActions>;
+    private trashOps: ReturnType<typeof createTrashActions>;
 
     constructor() {
         this.refresh = createFeedRefresher(this);
         this.feedOps = createFeedActions(this);
         this.articleOps = createArticleActions(this);
+        this.backupOps = createBackupActions(this);
+        this.trashOps = createTrashActions(this);
         registerShortcuts(this);
         setupKeyHandler(this);
         this.initStore();
@@ -115,6 +158,7 @@ class AppStateImpl {
     }
 
     refreshAllFeeds = () => this.refresh.refreshAllFeeds();
+    refreshDueFeeds = () => this.refresh.refreshDueFeeds();
     requestRefreshFeed = (feedId: number) => this.refresh.requestRefreshFeed(feedId);
     requestRefreshFolder = (folderId: number) => this.refresh.requestRefreshFolder(folderId);
 
@@ -125,7 +169,14 @@ class AppStateImpl {
     fetchFullContent = (article: Article) => this.articleOps.fetchFullContent(article);
 
     markAllRead = () => this.feedOps.markAllRead();
-    addFeed = (url: string, folderId?: number | null) => this.feedOps.addFeed(url, folderId);
+    addFeed = (
+        url: string,
+        folderId?: number | null,
+        allowPrivateNetwork?: boolean,
+        watch?: WatchConfig | null,
+    ) => this.feedOps.addFeed(url, folderId, allowPrivateNetwork, watch);
+    chooseFeed = (feedUrl: string) => this.feedOps.chooseFeed(feedUrl);
+    cancelFeedChoice = () => this.feedOps.cancelFeedChoice();
     createFolder = (name: string) => this.feedOps.createFolder(name);
     importOpml = () => this.feedOps.importOpml();
     exportOpml = () => this.feedOps.exportOpml();
@@ -135,11 +186,80 @@ class AppStateImpl {
     deleteFolder = (id: number) => this.feedOps.deleteFolder(id);
     moveFeed = (feedId: number, folderId: number) => this.feedOps.moveFeed(feedId, folderId);
 
+    listBackups = (): Promise<BackupInfo[]> => this.backupOps.listBackups();
+    createBackup = () => this.backupOps.createBackup();
+    restoreBackup = (name: string) => this.backupOps.restoreBackup(name);
+    exportBackup = (name: string) => this.backupOps.exportBackup(name);
+    importBackup = () => this.backupOps.importBackup();
+
+    listTrash = (): Promise<TrashItem[]> => this.trashOps.listTrash();
+    restoreFromTrash = (itemType: TrashItem['item_type'], id: number) =>
+        this.trashOps.restoreFromTrash(itemType, id);
+    emptyTrash = (onEmptied: () => void) => this.trashOps.emptyTrash(onEmptied);
+    offerUndo = (itemType: TrashItem['item_type'], id: number, message: string) =>
+        this.trashOps.offerUndo(itemType, id, message);
+    undo = () => this.trashOps.undo();
+    dismissUndo = () => this.trashOps.dismissUndo();
+
+    /** Sorts the open feed or folder, saved as its override; other views change the global order. */
     async setSortOrder(order: SortOrder) {
-        if (this.sortOrder !== order) {
-            this.sortOrder = order;
-            await this.reloadCurrentArticleList();
+        if (this.sortOrder === order) return;
+        this.sortOrder = order;
+        const target = this.sortTarget();
+        if (target) {
+            try {
+                const settings = await invoke<FeedSettings>('get_feed_settings', target);
+                await invoke('save_feed_settings', {
+                    ...target,
+                    settings: { ...settings, sort_order: order },
+                });
+                this.invalidateFeedSettings();
+            } catch (e) {
+                this.alert(`Failed to save sort order: ${errorMessage(e)}`);
+            }
+        } else {
+            await this.saveSettings({ ...this.settings, sort_order: order }, false);
         }
+        await this.reloadCurrentArticleList();
+    }
+
+    private sortTarget(): { targetType: 'feed' | 'folder'; id: number } | null {
+        if (this.searchQuery.trim()) return null;
+        if (this.selectedFeedId && this.selectedFeedId > 0) {
+            return { targetType: 'feed', id: this.selectedFeedId };
+        }
+        if (this.selectedFolderId && !this.selectedFeedId) {
+            return { targetType: 'folder', id: this.selectedFolderId };
+        }
+        return null;
+    }
+
+    /** Feed settings after feed, folder and global values are resolved; cached per feed. */
+    async getEffectiveFeedSettings(feedId: number): Promise<ResolvedFeedSettings | null> {
+        const cached = this.effectiveFeedSettings.get(feedId);
+        if (cached) return cached;
+        try {
+            const resolved = await invoke<ResolvedFeedSettings>('get_effective_feed_settings', {
+                feedId,
+            });
+            this.effectiveFeedSettings.set(feedId, resolved);
+            return resolved;
+        } catch (e) {
+            console.error(`Failed to load settings for feed ${feedId}:`, e);
+            return null;
+        }
+    }
+
+    invalidateFeedSettings() {
+        this.effectiveFeedSettings.clear();
+    }
+
+    openFeedSettings(type: 'feed' | 'folder', id: number, name: string) {
+        this.feedSettingsTarget = { type, id, name };
+    }
+
+    closeFeedSettings() {
+        this.feedSettingsTarget = null;
     }
 
     async setSearch(query: string) {
@@ -282,15 +402,29 @@ class AppStateImpl {
     }
     closeSettings() {
         this.showSettings = false;
+        this.settingsError = null;
+    }
+    openTrash() {
+        this.showTrash = true;
+    }
+    closeTrash() {
+        this.showTrash = false;
     }
 
     async saveSettings(newSettings: AppSettings, closeModal = true) {
         try {
             await invoke('save_app_settings', { newSettings });
             this.settings = newSettings;
+            this.settingsError = null;
+            this.invalidateFeedSettings();
             if (closeModal) this.closeSettings();
         } catch (e) {
-            this.alert(`Failed to save settings: ${e}`);
+            // Autosave runs on every edit, so report half-typed values inline.
+            if (!closeModal && isAppError(e) && e.kind === 'invalid_input') {
+                this.settingsError = e.message;
+            } else {
+                this.alert(`Failed to save settings: ${errorMessage(e)}`);
+            }
         }
     }
 
@@ -354,12 +488,10 @@ class AppStateImpl {
     private async initStore() {
         const storedNav = localStorage.getItem('navWidth');
         const storedList = localStorage.getItem('listWidth');
-        const storedSort = localStorage.getItem('sortOrder');
         const storedLastRefreshed = localStorage.getItem('lastRefreshed');
 
         if (storedNav) this.navWidth = parseInt(storedNav);
         if (storedList) this.listWidth = parseInt(storedList);
-        if (storedSort === 'asc' || storedSort === 'desc') this.sortOrder = storedSort;
 
         if (storedLastRefreshed) {
             try {
@@ -375,17 +507,15 @@ class AppStateImpl {
         try {
             const s = await invoke<AppSettings>('get_app_settings');
             this.settings = s;
-            if (this.settings.auto_update_interval_minutes > 0) {
-                const intervalMs = this.settings.auto_update_interval_minutes * 60 * 1000;
-                setInterval(() => this.refreshAllFeeds(), intervalMs);
-            }
+            this.sortOrder = s.sort_order === 'asc' ? 'asc' : 'desc';
+            setInterval(() => this.refreshDueFeeds(), DUE_CHECK_INTERVAL_MS);
         } catch (e) {
             console.error('Failed to load settings', e);
         }
 
         await this.loadShortcutSettings();
         await this.refreshFolders();
-        this.refreshAllFeeds();
+        this.refreshDueFeeds();
 
         const viewType = this.settings.default_view_type;
         const viewId = this.settings.default_view_id;
@@ -399,7 +529,6 @@ class AppStateImpl {
             $effect(() => {
                 localStorage.setItem('navWidth', this.navWidth.toString());
                 localStorage.setItem('listWidth', this.listWidth.toString());
-                localStorage.setItem('sortOrder', this.sortOrder);
             });
         });
     }
@@ -1,8 +1,24 @@
-import type { AppSettings, Article, Folder } from './types';
+import type {
+    AppSettings,
+    Article,
+    BackupInfo,
+    Folder,
+    PendingFeedChoice,
+    ResolvedFeedSettings,
+    TrashItem,
+    WatchConfig,
+} from './types';
 
 export type Theme = 'light' | 'dark' | 'sepia' | 'system';
 export type SortOrder = 'desc' | 'asc';
 
+/** The last deleted feed or folder, offered back until the undo toast closes. */
+export interface UndoDelete {
+    item_type: TrashItem['item_type'];
+    id: number;
+    message: string;
+}
+
 export interface AppState {
     folders: Folder[];
     articles: Article[];
@@ -16,13 +32,18 @@ export interface AppState {
     sortOrder: SortOrder;
     settings: AppSettings;
     showSettings: boolean;
+    showTrash: boolean;
+    undoDelete: UndoDelete | null;
+    settingsError: string | null;
+    feedSettingsTarget: { type: 'feed' | 'folder'; id: number; name: string } | null;
     showAddDialog: boolean;
+    feedChoice: PendingFeedChoice | null;
     expandedFolders: Set<number>;
     focusedPane: 'nav' | 'list' | 'reading';
     customShortcuts: Record<string, string>;
     navWidth: number;
     listWidth: number;
-    page: number;
+    cursor: string | null;
     readonly pageSize: number;
     hasMore: boolean;
     latestHours: number;
@@ -43,6 +64,7 @@ export interface AppState {
     refreshFolders(): Promise<void>;
     reloadCurrentArticleList(): Promise<void>;
     refreshAllFeeds(): Promise<void>;
+    refreshDueFeeds(): Promise<void>;
     requestRefreshFeed(feedId: number): Promise<void>;
     requestRefreshFolder(folderId: number): Promise<void>;
     selectFeed(feedId: number): Promise<void>;
@@ -52,7 +74,14 @@ export interface AppState {
     fetchFullContent(article: Article): Promise<string | null>;
     loadMore(): Promise<void>;
     markAllRead(): Promise<void>;
-    addFeed(url: string, folderId?: number | null): Promise<void>;
+    addFeed(
+        url: string,
+        folderId?: number | null,
+        allowPrivateNetwork?: boolean,
+        watch?: WatchConfig | null,
+    ): Promise<void>;
+    chooseFeed(feedUrl: string): Promise<void>;
+    cancelFeedChoice(): void;
     createFolder(name: string): Promise<void>;
     importOpml(): Promise<void>;
     exportOpml(): Promise<void>;
@@ -61,6 +90,17 @@ export interface AppState {
     deleteFeed(id: number): Promise<void>;
     deleteFolder(id: number): Promise<void>;
     moveFeed(feedId: number, folderId: number): Promise<void>;
+    listBackups(): Promise<BackupInfo[]>;
+    createBackup(): Promise<void>;
+    restoreBackup(name: string): void;
+    exportBackup(name: string): Promise<void>;
+    importBackup(): Promise<void>;
+    listTrash(): Promise<TrashItem[]>;
+    restoreFromTrash(itemType: TrashItem['item_type'], id: number): Promise<void>;
+    emptyTrash(onEmptied: () => void): void;
+    offerUndo(itemType: TrashItem['item_type'], id: number, message: string): void;
+    undo(): Promise<void>;
+    dismissUndo(): void;
     navUp(): void;
     navDown(): void;
     articleUp(): void;
@@ -68,10 +108,16 @@ export interface AppState {
     setShortcut(commandId: string, key: string): void;
     resetShortcut(commandId: string): void;
     setSortOrder(order: SortOrder): Promise<void>;
+    getEffectiveFeedSettings(feedId: number): Promise<ResolvedFeedSettings | null>;
+    invalidateFeedSettings(): void;
+    openFeedSettings(type: 'feed' | 'folder', id: number, name: string): void;
+    closeFeedSettings(): void;
     setSearch(query: string): Promise<void>;
     setTheme(theme: Theme): void;
     openSettings(): void;
     closeSettings(): void;
+    openTrash(): void;
+    closeTrash(): void;
     saveSettings(newSettings: AppSettings, closeModal?: boolean): Promise<void>;
     confirm(message: string, onConfirm: () => void): void;
     alert(message: string): void;
@@ -0,0 +1,60 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { AppState } from './storeTypes';
+import type { TrashItem } from './types';
+import { errorMessage } from './utils/errors';
+
+/** How long the undo toast stays up after a delete. */
+const UNDO_TIMEOUT_MS = 8000;
+
+export function createTrashActions(state: AppState) {
+    let undoTimer: ReturnType<typeof setTimeout> | null = null;
+
+    async function listTrash(): Promise<TrashItem[]> {
+        try {
+            return await invoke<TrashItem[]>('get_trash');
+        } catch (e) {
+            console.error('Failed to list trash:', e);
+            return [];
+        }
+    }
+
+    async function restoreFromTrash(itemType: TrashItem['item_type'], id: number) {
+        try {
+            await invoke('restore_from_trash', { itemType, id });
+            await state.refreshFolders();
+        } catch (e) {
+            state.alert(`Restore failed: ${errorMessage(e)}`);
+        }
+    }
+
+    function emptyTrash(onEmptied: () => void) {
+        state.confirm('Permanently delete everything in the trash?', async () => {
+            try {
+                await invoke<number>('empty_trash');
+                onEmptied();
+            } catch (e) {
+                state.alert(`Emptying trash failed: ${errorMessage(e)}`);
+            }
+        });
+    }
+
+    function offerUndo(itemType: TrashItem['item_type'], id: number, message: string) {
+        if (undoTimer) clearTimeout(undoTimer);
+        state.undoDelete = { item_type: itemType, id, message };
+        undoTimer = setTimeout(dismissUndo, UNDO_TIMEOUT_MS);
+    }
+
+    async function undo() {
+        const item = state.undoDelete;
+        dismissUndo();
+        if (item) await restoreFromTrash(item.item_type, item.id);
+    }
+
+    function dismissUndo() {
+        if (undoTimer) clearTimeout(undoTimer);
+        undoTimer = null;
+        state.undoDelete = null;
+    }
+
+    return { listTrash, restoreFromTrash, emptyTrash, offerUndo, undo, dismissUndo };
+}
@@ -13,6 +13,8 @@ export interface Feed {
     has_error: boolean;
     feed_type?: string;
     content_hash?: string | null;
+    /** Why the last refresh failed, e.g. a feed command's stderr. */
+    last_error?: string | null;
 }
 
 export interface Article {
@@ -25,9 +27,35 @@ export interface Article {
     timestamp: number;
     is_read: boolean;
     is_saved: boolean;
+    fetched_at: number;
+}
+
+export interface ArticlePage {
+    articles: Article[];
+    next_cursor: string | null;
+    sort_asc: boolean;
+}
+
+export type ArticleSort = 'date' | 'added' | 'title' | 'feed';
+
+export interface ArticleQuery {
+    feed_id?: number;
+    folder_id?: number;
+    search?: string;
+    author?: string;
+    since?: number;
+    until?: number;
+    date_field?: 'published' | 'fetched';
+    unread_only?: boolean;
+    saved_only?: boolean;
+    sort?: ArticleSort;
+    sort_asc?: boolean;
+    cursor?: string | null;
+    limit?: number;
 }
 
 export interface AppSettings {
+    version?: number;
     feed_refresh_debounce_minutes: number;
     refresh_all_debounce_minutes: number;
     auto_update_interval_minutes: number;
@@ -36,4 +64,159 @@ export interface AppSettings {
     default_view_id: number;
     auto_collapse_folders: boolean;
     mark_feed_read_on_exit: boolean;
+    latest_by_fetch_time: boolean;
+    trash_retention_days: number;
+    keep_saved_on_delete: boolean;
+    backup_interval_hours: number;
+    backup_keep_count: number;
+    article_retention_days: number;
+    full_text: boolean;
+    reader_mode: boolean;
+    mark_read_on_open: boolean;
+    sort_order: 'asc' | 'desc';
+    proxy_url: string;
+    connect_timeout_seconds: number;
+    request_timeout_seconds: number;
+    user_agent: string;
+    compression: boolean;
+    max_response_mb: number;
+    ca_cert_paths: string[];
+}
+
+/** Per-feed or per-folder overrides; null inherits from the next level up. */
+export interface FeedSettings {
+    refresh_interval_minutes: number | null;
+    retention_days: number | null;
+    full_text: boolean | null;
+    user_agent: string | null;
+    reader_mode: boolean | null;
+    mark_read_on_open: boolean | null;
+    notifications_muted: boolean | null;
+    sort_order: 'asc' | 'desc' | null;
+    allow_private_network: boolean | null;
+}
+
+export interface ResolvedFeedSettings {
+    refresh_interval_minutes: number;
+    retention_days: number;
+    full_text: boolean;
+    user_agent: string | null;
+    reader_mode: boolean;
+    mark_read_on_open: boolean;
+    notifications_muted: boolean;
+    sort_order: 'asc' | 'desc';
+    allow_private_network: boolean;
+}
+
+/** Credentials to save for a feed. Omitted/null keeps the stored value; '' clears it. */
+export interface FeedAuth {
+    username?: string | null;
+    password?: string | null;
+    bearer_token?: string | null;
+    cookies?: string | null;
+    headers?: Record<string, string> | null;
+}
+
+/** Which credentials a feed has; secret values never leave the backend. */
+export interface FeedAuthInfo {
+    username: string | null;
+    has_password: boolean;
+    has_bearer_token: boolean;
+    has_cookies: boolean;
+    header_names: string[];
+}
+
+/**
+ * CSS-selector rules for a website feed. Selectors besides `item_selector` are matched
+ * inside each item; URL patterns match anywhere in the link, `*` being a wildcard.
+ */
+export interface ScrapeConfig {
+    item_selector: string;
+    title_selector: string | null;
+    link_selector: string | null;
+    date_selector: string | null;
+    summary_selector: string | null;
+    include_patterns: string[];
+    exclude_patterns: string[];
+    /** Also read dates, authors and descriptions from each new article's page. */
+    fetch_article_pages: boolean;
+}
+
+/**
+ * What a `watch` feed compares between refreshes: the part of the page picked by
+ * `selector` (the whole page if unset), minus anything matching `ignore_selectors`.
+ */
+export interface WatchConfig {
+    selector: string | null;
+    ignore_selectors: string[];
+    /** Changes smaller than this many characters wait until they add up. */
+    min_changed_chars: number;
+}
+
+/** Where discovery found a feed. */
+export type FeedSource = 'direct' | 'platform' | 'link' | 'anchor' | 'well_known' | 'sitemap';
+
+/** A working feed found for a page, offered to the user before subscribing. */
+export interface FeedCandidate {
+    url: string;
+    title: string;
+    format: 'rss' | 'atom' | 'json' | 'sitemap';
+    /** Child sitemaps when the candidate is a sitemap index. */
+    entry_count: number;
+    is_comments: boolean;
+    source: FeedSource;
+}
+
+/** Several feeds were found for a URL and the user has to pick one. */
+export interface PendingFeedChoice {
+    url: string;
+    folderId: number | null;
+    allowPrivateNetwork: boolean;
+    candidates: FeedCandidate[];
+}
+
+export interface DbHealth {
+    integrity_ok: boolean | null;
+    integrity_problems: string[];
+    last_check_at: number;
+    last_full_check_at: number;
+    last_analyze_at: number;
+    last_maintenance_at: number;
+    last_vacuumed_pages: number;
+    auto_vacuum: string;
+    page_size: number;
+    page_count: number;
+    freelist_count: number;
+    size_bytes: number;
+}
+
+export interface BackupInfo {
+    name: string;
+    size: number;
+    created_at: number;
+}
+
+export interface TrashItem {
+    item_type: 'feed' | 'folder';
+    id: number;
+    name: string;
+    deleted_at: number;
+    folder_name: string | null;
+    item_count: number;
+}
+
+export type AppErrorKind =
+    | 'database'
+    | 'not_found'
+    | 'network'
+    | 'timeout'
+    | 'parse'
+    | 'io'
+    | 'invalid_input';
+
+export interface AppError {
+    kind: AppErrorKind;
+    message: string;
+    feed_id: number | null;
+    status: number | null;
 }
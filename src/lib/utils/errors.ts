@@ -0,0 +1,23 @@
+import type { AppError } from '../types';
+
+export function isAppError(e: unknown): e is AppError {
+    return typeof e === 'object' && e !== null && 'kind' in e && 'message' in e;
+}
+
+export function errorMessage(e: unknown): string {
+    if (!isAppError(e)) return String(e);
+    switch (e.kind) {
+        case 'timeout':
+            return `The server took too long to respond. ${e.message}`;
+        case 'network':
+            return e.status === 404
+                ? 'The address could not be found (HTTP 404).'
+                : `Could not reach the server. ${e.message}`;
+        case 'parse':
+            return `The content could not be read as a feed. ${e.message}`;
+        case 'not_found':
+            return `Not found. ${e.message}`;
+        default:
+            return e.message;
+    }
+}